@@ -0,0 +1,7 @@
+// Generation worker, see src/worker.rs. Requests arriving while the module
+// loads are handled in order once it is ready.
+import init, { on_worker_message } from "./assets/dioxus/wasm-island.js";
+
+const ready = init();
+
+onmessage = (event) => ready.then(() => on_worker_message(event.data));
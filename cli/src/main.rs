@@ -0,0 +1,347 @@
+use clap::{parser::ValueSource, ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser};
+use map_generator::{
+    defaults::*, obj_material, CellReturn, ColorRamp, Distance, FractalMode, Generator,
+    GeneratorSettings, GeneratorType, HeightmapFormat, HeightmapImage, HeightmapKind,
+    Interpolation, IslandCurve, IslandMask, MeshColors, MeshFormat, Neighbourhood, NoiseKind,
+    RangeMode, SettingsFormat, Tiling, WarpMode,
+};
+use std::{
+    error::Error,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use strum::IntoEnumIterator;
+
+fn parse_enum<T: IntoEnumIterator + ToString>(value: &str) -> Result<T, String> {
+    let simplify = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+
+    T::iter()
+        .find(|variant| simplify(&variant.to_string()) == simplify(value))
+        .ok_or_else(|| {
+            format!(
+                "expected one of: {}",
+                T::iter()
+                    .map(|variant| variant.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+fn parse_seeds(value: &str) -> Result<RangeInclusive<u64>, String> {
+    let parse = |s: &str| s.trim().parse::<u64>().map_err(|e| e.to_string());
+
+    if let Some((start, end)) = value.split_once("..=") {
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start > end {
+            return Err("start must not exceed end".to_string());
+        }
+        Ok(start..=end)
+    } else if let Some((start, end)) = value.split_once("..") {
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start > end {
+            return Err("start must not exceed end".to_string());
+        }
+        if start == end {
+            return Err("empty seed range".to_string());
+        }
+        Ok(start..=end - 1)
+    } else {
+        let seed = parse(value)?;
+        Ok(seed..=seed)
+    }
+}
+
+fn settings_format(path: &Path) -> Result<SettingsFormat, String> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(SettingsFormat::from_extension)
+        .ok_or_else(|| {
+            format!(
+                "expected a .json or .toml settings file: {}",
+                path.display()
+            )
+        })
+}
+
+/// Settings of the `--settings` file, with those given on the command line instead.
+fn merge(
+    file: &GeneratorSettings,
+    args: &GeneratorSettings,
+    matches: &ArgMatches,
+) -> Result<GeneratorSettings, serde_json::Error> {
+    let mut merged = serde_json::to_value(file)?;
+    let args = serde_json::to_value(args)?;
+
+    for arg in Args::command().get_arguments() {
+        let id = arg.get_id().as_str();
+        if matches.value_source(id) == Some(ValueSource::CommandLine) {
+            if let Some(value) = args.get(id) {
+                merged[id] = value.clone();
+            }
+        }
+    }
+
+    serde_json::from_value(merged)
+}
+
+/// Generate maps and write them as PNG images.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Seed, or range of seeds (`10..20` or `10..=19`) to generate a batch
+    #[arg(short, long, value_parser = parse_seeds)]
+    seed: Option<RangeInclusive<u64>>,
+    /// Output path, `{seed}` is replaced by the seed of each map
+    #[arg(short, long, default_value = "map-{seed}.png")]
+    output: String,
+    /// JSON or TOML settings to start from, options given on the command line override them
+    #[arg(long)]
+    settings: Option<PathBuf>,
+    /// Write the settings of the first map to this JSON or TOML file
+    #[arg(long)]
+    save_settings: Option<PathBuf>,
+    /// Write the heightmap in this format instead of rendering the map, with the
+    /// extension of the format
+    #[arg(long, value_parser = parse_enum::<HeightmapFormat>)]
+    heightmap_format: Option<HeightmapFormat>,
+    /// Height mapped to the lowest value of the heightmap format
+    #[arg(long, default_value_t = 0.0)]
+    height_min: f64,
+    /// Height mapped to the highest value of the heightmap format
+    #[arg(long, default_value_t = 1.0)]
+    height_max: f64,
+    /// Write a mesh of the heightmap in this format instead of rendering the map,
+    /// with the extension of the format
+    #[arg(long, value_parser = parse_enum::<MeshFormat>, conflicts_with = "heightmap_format")]
+    mesh_format: Option<MeshFormat>,
+    /// How the colored map is applied to the mesh, textures are written next to it
+    #[arg(long, default_value_t = MeshColors::default(), value_parser = parse_enum::<MeshColors>)]
+    mesh_colors: MeshColors,
+    /// Horizontal distance between two mesh vertices
+    #[arg(long, default_value_t = 1.0)]
+    spacing: f64,
+    /// Mesh height of the highest point of the heightmap
+    #[arg(long, default_value_t = 64.0)]
+    vertical_scale: f64,
+    #[arg(short = 't', long = "type", default_value_t = DEFAULT_GENERATOR_TYPE, value_parser = parse_enum::<GeneratorType>)]
+    generator_type: GeneratorType,
+    #[arg(long, default_value_t = DEFAULT_INTERPOLATION, value_parser = parse_enum::<Interpolation>)]
+    interpolation: Interpolation,
+    #[arg(long, default_value_t = DEFAULT_NOISE, value_parser = parse_enum::<NoiseKind>)]
+    noise: NoiseKind,
+    #[arg(long, default_value_t = DEFAULT_DISTANCE, value_parser = parse_enum::<Distance>)]
+    distance: Distance,
+    #[arg(long, default_value_t = DEFAULT_CELL_RETURN, value_parser = parse_enum::<CellReturn>)]
+    cell_return: CellReturn,
+    #[arg(long, default_value_t = DEFAULT_TILING, value_parser = parse_enum::<Tiling>)]
+    tiling: Tiling,
+    #[arg(long, default_value_t = DEFAULT_WIDTH)]
+    width: usize,
+    #[arg(long, default_value_t = DEFAULT_HEIGHT)]
+    height: usize,
+    #[arg(long, default_value_t = DEFAULT_HEIGHTMAP, value_parser = parse_enum::<HeightmapKind>)]
+    heightmap: HeightmapKind,
+    /// PNG or PGM image used by the `Image` heightmap
+    #[arg(long)]
+    image: Option<PathBuf>,
+    /// Scale of the noise domain, higher values give smaller features
+    #[arg(long, default_value_t = DEFAULT_FREQUENCY)]
+    frequency: f64,
+    /// Horizontal offset of the noise domain, the shorter map side being one unit
+    #[arg(long, default_value_t = DEFAULT_OFFSET_X, allow_negative_numbers = true)]
+    offset_x: f64,
+    /// Vertical offset of the noise domain, the shorter map side being one unit
+    #[arg(long, default_value_t = DEFAULT_OFFSET_Y, allow_negative_numbers = true)]
+    offset_y: f64,
+    /// Rotation of the noise domain in degrees, ignored when tiling
+    #[arg(long, default_value_t = DEFAULT_ROTATION, allow_negative_numbers = true)]
+    rotation: f64,
+    #[arg(long, default_value_t = DEFAULT_OCTAVE)]
+    octave: u64,
+    #[arg(long, default_value_t = DEFAULT_LACUNARITY)]
+    lacunarity: f64,
+    #[arg(long, default_value_t = DEFAULT_PERSISTENCE)]
+    persistence: f64,
+    #[arg(long, default_value_t = DEFAULT_FRACTAL_MODE, value_parser = parse_enum::<FractalMode>)]
+    fractal_mode: FractalMode,
+    #[arg(long, default_value_t = DEFAULT_RANGE_MODE, value_parser = parse_enum::<RangeMode>)]
+    range_mode: RangeMode,
+    #[arg(long, default_value_t = DEFAULT_WARP_MODE, value_parser = parse_enum::<WarpMode>)]
+    warp_mode: WarpMode,
+    #[arg(long, default_value_t = DEFAULT_WARP_STRENGTH)]
+    warp_strength: f64,
+    #[arg(long, default_value_t = DEFAULT_WARP_FREQUENCY)]
+    warp_frequency: f64,
+    #[arg(long, default_value_t = DEFAULT_EROSION_ITERATIONS)]
+    erosion_iterations: u64,
+    #[arg(long, default_value_t = DEFAULT_EROSION_INERTIA)]
+    erosion_inertia: f64,
+    #[arg(long, default_value_t = DEFAULT_SEDIMENT_CAPACITY)]
+    sediment_capacity: f64,
+    #[arg(long, default_value_t = DEFAULT_DEPOSITION_RATE)]
+    deposition_rate: f64,
+    #[arg(long, default_value_t = DEFAULT_EROSION_RATE)]
+    erosion_rate: f64,
+    #[arg(long, default_value_t = DEFAULT_EVAPORATION)]
+    evaporation: f64,
+    #[arg(long, default_value_t = DEFAULT_THERMAL_ITERATIONS)]
+    thermal_iterations: u64,
+    #[arg(long, default_value_t = DEFAULT_TALUS_ANGLE)]
+    talus_angle: f64,
+    #[arg(long, default_value_t = DEFAULT_NEIGHBOURHOOD, value_parser = parse_enum::<Neighbourhood>)]
+    neighbourhood: Neighbourhood,
+    #[arg(long, default_value_t = DEFAULT_ISLAND_MASK, value_parser = parse_enum::<IslandMask>)]
+    island_mask: IslandMask,
+    /// Height of the `Curve` island mask by distance to the center, as `distance:height`
+    /// points separated by commas, e.g. `0:1,0.5:0.95,1:0`
+    #[arg(long)]
+    island_curve: Option<IslandCurve>,
+    #[arg(long, default_value_t = DEFAULT_ISLAND_CENTER_X)]
+    island_center_x: f64,
+    #[arg(long, default_value_t = DEFAULT_ISLAND_CENTER_Y)]
+    island_center_y: f64,
+    #[arg(long, default_value_t = DEFAULT_ISLAND_RADIUS)]
+    island_radius: f64,
+    #[arg(long, default_value_t = DEFAULT_ISLAND_STRENGTH)]
+    island_strength: f64,
+    #[arg(long, default_value_t = DEFAULT_FLATTEN, action = ArgAction::Set)]
+    flatten: bool,
+    #[arg(long, default_value_t = DEFAULT_USE_SHADING, action = ArgAction::Set)]
+    use_shading: bool,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches)?;
+
+    let settings = GeneratorSettings {
+        seed: args
+            .seed
+            .as_ref()
+            .map_or(DEFAULT_SEED, |seeds| *seeds.start()),
+        interpolation: args.interpolation,
+        noise: args.noise,
+        distance: args.distance,
+        cell_return: args.cell_return,
+        width: args.width,
+        height: args.height,
+        heightmap: args.heightmap,
+        tiling: args.tiling,
+        frequency: args.frequency,
+        offset_x: args.offset_x,
+        offset_y: args.offset_y,
+        rotation: args.rotation,
+        octave: args.octave,
+        lacunarity: args.lacunarity,
+        persistence: args.persistence,
+        fractal_mode: args.fractal_mode,
+        warp_mode: args.warp_mode,
+        warp_strength: args.warp_strength,
+        warp_frequency: args.warp_frequency,
+        range_mode: args.range_mode,
+        erosion_iterations: args.erosion_iterations,
+        erosion_inertia: args.erosion_inertia,
+        sediment_capacity: args.sediment_capacity,
+        deposition_rate: args.deposition_rate,
+        erosion_rate: args.erosion_rate,
+        evaporation: args.evaporation,
+        thermal_iterations: args.thermal_iterations,
+        talus_angle: args.talus_angle,
+        neighbourhood: args.neighbourhood,
+        color_ramp: ColorRamp::from(DEFAULT_COLOR_RAMP.to_vec()),
+        island_mask: args.island_mask,
+        island_curve: args
+            .island_curve
+            .clone()
+            .unwrap_or_else(|| IslandCurve::from(DEFAULT_ISLAND_CURVE.to_vec())),
+        island_center_x: args.island_center_x,
+        island_center_y: args.island_center_y,
+        island_radius: args.island_radius,
+        island_strength: args.island_strength,
+        flatten: args.flatten,
+        use_shading: args.use_shading,
+        light_color: DEFAULT_LIGHT,
+        dark_color: DEFAULT_DARK,
+        light_position: DEFAULT_LIGHT_POSITION,
+    };
+    let settings = match &args.settings {
+        Some(path) => {
+            let format = settings_format(path)?;
+            let file = GeneratorSettings::decode(&std::fs::read_to_string(path)?, format)?;
+            merge(&file, &settings, &matches)?
+        }
+        None => settings,
+    };
+    settings.validate()?;
+
+    let seeds = args.seed.clone().unwrap_or(settings.seed..=settings.seed);
+
+    if seeds.start() != seeds.end() && !args.output.contains("{seed}") {
+        return Err("the output path must contain `{seed}` when generating several seeds".into());
+    }
+
+    if let Some(path) = &args.save_settings {
+        std::fs::write(path, settings.encode(settings_format(path)?)?)?;
+    }
+
+    let image = match &args.image {
+        Some(path) => Some(HeightmapImage::decode(&std::fs::read(path)?)?),
+        None => None,
+    };
+
+    let mut generator = Generator::from(settings);
+    generator.set_image(image);
+
+    for seed in seeds {
+        generator.set_seed(seed);
+
+        let mut path = PathBuf::from(args.output.replace("{seed}", &seed.to_string()));
+        let data = if let Some(format) = args.heightmap_format {
+            path.set_extension(format.extension());
+            generator
+                .export_heightmap()
+                .format(format)
+                .min(args.height_min)
+                .max(args.height_max)
+                .call()?
+        } else if let Some(format) = args.mesh_format {
+            // The texture of the mesh is written with the PNG extension.
+            path.set_extension(format.extension());
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("map");
+
+            // Textured OBJ meshes reference a material and the colored map next to them.
+            if args.mesh_colors == MeshColors::Texture && format == MeshFormat::Obj {
+                std::fs::write(path.with_extension("mtl"), obj_material(name))?;
+                std::fs::write(
+                    path.with_extension("png"),
+                    generator.export_png(GeneratorType::ColoredMap)?,
+                )?;
+            }
+
+            generator
+                .export_mesh()
+                .format(format)
+                .colors(args.mesh_colors)
+                .spacing(args.spacing)
+                .vertical_scale(args.vertical_scale)
+                .name(name)
+                .call()?
+        } else {
+            generator.export_png(args.generator_type)?
+        };
+        std::fs::write(&path, data)?;
+
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use super::{Generator, GeneratorType, Noise};
+use bon::bon;
+
+/// Coordinate of sample `index` out of `count` between `min` and `max`, with
+/// `border` extra samples on each side. Interpolating this way puts the first
+/// and last inner samples exactly on `min` and `max`.
+fn coordinate(min: f64, max: f64, index: usize, count: usize, border: usize) -> f64 {
+    let t = (index as f64 - border as f64) / (count.max(2) - 1) as f64;
+    min * (1.0 - t) + max * t
+}
+
+fn points(
+    (x_min, y_min): (f64, f64),
+    (x_max, y_max): (f64, f64),
+    width: usize,
+    height: usize,
+    border: usize,
+) -> impl Iterator<Item = (f64, f64)> {
+    (0..height + 2 * border).flat_map(move |y| {
+        (0..width + 2 * border).map(move |x| {
+            (
+                coordinate(x_min, x_max, x, width, border),
+                coordinate(y_min, y_max, y, height, border),
+            )
+        })
+    })
+}
+
+#[bon]
+impl Generator {
+    /// Heights of the world-space rectangle between (`x_min`, `y_min`) and
+    /// (`x_max`, `y_max`), sampled on a `width` by `height` grid whose outer
+    /// rows and columns lie on the edges of the rectangle.
+    ///
+    /// Chunks always use the `Fractal` heightmap, whose maps cover the unit
+    /// square, with the fixed range so that adjacent chunks sharing an edge
+    /// agree on it exactly, whatever the `heightmap` and `range_mode` of the
+    /// generator. The domain rotates about the center of the unit square,
+    /// whatever the size of the map of the generator. Erosion and the island
+    /// mask need the whole map and are not applied.
+    #[builder]
+    pub fn chunk_heightmap(
+        &self,
+        x_min: f64,
+        y_min: f64,
+        x_max: f64,
+        y_max: f64,
+        width: usize,
+        height: usize,
+    ) -> Vec<f64> {
+        self.flatten_curve(self.sample_fractal(points(
+            (x_min, y_min),
+            (x_max, y_max),
+            width,
+            height,
+            0,
+        )))
+    }
+
+    /// RGBA pixels of a chunk, see `chunk_heightmap`. Like it, chunks ignore
+    /// the `heightmap` and `range_mode` of the generator: they are always
+    /// sampled from the `Fractal` heightmap with the fixed range. The `Noise`
+    /// view shows the first octave of the fractal.
+    #[builder]
+    pub fn generate_chunk(
+        &self,
+        generator_type: GeneratorType,
+        x_min: f64,
+        y_min: f64,
+        x_max: f64,
+        y_max: f64,
+        width: usize,
+        height: usize,
+    ) -> Vec<u8> {
+        let (min, max) = ((x_min, y_min), (x_max, y_max));
+
+        match generator_type {
+            GeneratorType::Noise => {
+                let noise = self.noise_type();
+                let warp = self.warp();
+                let transform = self.world_transform();
+
+                points(min, max, width, height, 0)
+                    .flat_map(|(x, y)| {
+                        let (x, y) = transform.apply(x, y);
+                        let (x, y) = warp.apply(x, y, (None, None));
+                        std::iter::repeat_n((noise.noise(x, y) * 255.0) as u8, 4)
+                    })
+                    .collect()
+            }
+            GeneratorType::Heightmap => self.render(
+                &self
+                    .chunk_heightmap()
+                    .x_min(x_min)
+                    .y_min(y_min)
+                    .x_max(x_max)
+                    .y_max(y_max)
+                    .width(width)
+                    .height(height)
+                    .call(),
+                width,
+                0..height,
+                generator_type,
+            ),
+            GeneratorType::ColoredMap => {
+                // Shading looks at the neighbours of each sample, a border of
+                // samples from the adjacent chunks keeps it continuous.
+                let heightmap =
+                    self.flatten_curve(self.sample_fractal(points(min, max, width, height, 1)));
+                let rgba = self.render(&heightmap, width + 2, 0..height + 2, generator_type);
+
+                rgba.chunks_exact((width + 2) * 4)
+                    .skip(1)
+                    .take(height)
+                    .flat_map(|row| &row[4..(width + 1) * 4])
+                    .copied()
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Generator, GeneratorSettings, GeneratorType};
+
+    const SIZE: usize = 17;
+
+    fn chunk(generator: &Generator, x: f64, y: f64) -> Vec<f64> {
+        generator
+            .chunk_heightmap()
+            .x_min(x)
+            .y_min(y)
+            .x_max(x + 1.0)
+            .y_max(y + 1.0)
+            .width(SIZE)
+            .height(SIZE)
+            .call()
+    }
+
+    fn pixels(generator: &Generator, x: f64, y: f64) -> Vec<u8> {
+        generator
+            .generate_chunk()
+            .generator_type(GeneratorType::ColoredMap)
+            .x_min(x)
+            .y_min(y)
+            .x_max(x + 1.0)
+            .y_max(y + 1.0)
+            .width(SIZE)
+            .height(SIZE)
+            .call()
+    }
+
+    fn column<T: Copy>(data: &[T], x: usize, channels: usize) -> Vec<T> {
+        (0..SIZE)
+            .flat_map(|y| &data[(y * SIZE + x) * channels..(y * SIZE + x + 1) * channels])
+            .copied()
+            .collect()
+    }
+
+    fn row<T: Copy>(data: &[T], y: usize, channels: usize) -> Vec<T> {
+        data[y * SIZE * channels..(y + 1) * SIZE * channels].to_vec()
+    }
+
+    #[test]
+    fn adjacent_chunks_share_their_edges() {
+        for rotation in [0.0, 30.0] {
+            let generator = Generator::from(GeneratorSettings {
+                rotation,
+                ..Default::default()
+            });
+            assert_edges_shared(&generator);
+        }
+    }
+
+    #[test]
+    fn chunks_do_not_depend_on_the_map_size() {
+        let generators = [(512, 512), (300, 100)].map(|(width, height)| {
+            Generator::from(GeneratorSettings {
+                width,
+                height,
+                rotation: 30.0,
+                ..Default::default()
+            })
+        });
+
+        assert_eq!(
+            chunk(&generators[0], 1.0, -2.0),
+            chunk(&generators[1], 1.0, -2.0)
+        );
+        assert_eq!(
+            pixels(&generators[0], 1.0, -2.0),
+            pixels(&generators[1], 1.0, -2.0)
+        );
+    }
+
+    fn assert_edges_shared(generator: &Generator) {
+        let (center, right, below) = (
+            chunk(generator, 0.0, 0.0),
+            chunk(generator, 1.0, 0.0),
+            chunk(generator, 0.0, 1.0),
+        );
+        assert_eq!(column(&center, SIZE - 1, 1), column(&right, 0, 1));
+        assert_eq!(row(&center, SIZE - 1, 1), row(&below, 0, 1));
+
+        let (center, right, below) = (
+            pixels(generator, 0.0, 0.0),
+            pixels(generator, 1.0, 0.0),
+            pixels(generator, 0.0, 1.0),
+        );
+        assert_eq!(column(&center, SIZE - 1, 4), column(&right, 0, 4));
+        assert_eq!(row(&center, SIZE - 1, 4), row(&below, 0, 4));
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Color(pub [u8; 4]);
 
 impl Color {
@@ -44,13 +44,15 @@ pub fn lerp(a: Color, b: Color, t: f64) -> Color {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ColorRampStep {
     pub color: Color,
     pub position: f64,
 }
 
-#[derive(Debug, Clone)]
+/// Serialized as its list of steps, which gets sorted again when read.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Vec<ColorRampStep>", into = "Vec<ColorRampStep>")]
 pub struct ColorRamp {
     steps: Vec<ColorRampStep>,
 }
@@ -59,14 +61,25 @@ impl From<Vec<ColorRampStep>> for ColorRamp {
     fn from(mut steps: Vec<ColorRampStep>) -> Self {
         ColorRamp {
             steps: {
-                steps.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+                steps.sort_by(|a, b| a.position.total_cmp(&b.position));
                 steps
             },
         }
     }
 }
 
+impl From<ColorRamp> for Vec<ColorRampStep> {
+    fn from(ramp: ColorRamp) -> Self {
+        ramp.steps
+    }
+}
+
 impl ColorRamp {
+    /// Steps sorted by position.
+    pub fn steps(&self) -> &[ColorRampStep] {
+        &self.steps
+    }
+
     pub fn get(&self, position: f64) -> Color {
         match self.steps.iter().position(|x| x.position >= position) {
             Some(0) => self.steps[0].color,
@@ -1,17 +1,39 @@
-use crate::map_generator::{
-    Color, ColorRampStep, GeneratorType, HeightmapKind, Interpolation, NoiseKind, Vec3,
+use crate::{
+    CellReturn, Color, ColorRampStep, Distance, FractalMode, GeneratorType, HeightmapKind,
+    Interpolation, IslandMask, Neighbourhood, NoiseKind, RangeMode, Tiling, Vec3, WarpMode,
 };
 
 pub const DEFAULT_GENERATOR_TYPE: GeneratorType = GeneratorType::ColoredMap;
 pub const DEFAULT_SEED: u64 = 5;
 pub const DEFAULT_INTERPOLATION: Interpolation = Interpolation::Cubic;
 pub const DEFAULT_HEIGHTMAP: HeightmapKind = HeightmapKind::Fractal;
+pub const DEFAULT_TILING: Tiling = Tiling::None;
 pub const DEFAULT_NOISE: NoiseKind = NoiseKind::Gradient;
+pub const DEFAULT_DISTANCE: Distance = Distance::Euclidean;
+pub const DEFAULT_CELL_RETURN: CellReturn = CellReturn::F1;
 pub const DEFAULT_WIDTH: usize = 512;
 pub const DEFAULT_HEIGHT: usize = 512;
+pub const DEFAULT_FREQUENCY: f64 = 1.0;
+pub const DEFAULT_OFFSET_X: f64 = 0.0;
+pub const DEFAULT_OFFSET_Y: f64 = 0.0;
+pub const DEFAULT_ROTATION: f64 = 0.0;
 pub const DEFAULT_OCTAVE: u64 = 8;
 pub const DEFAULT_LACUNARITY: f64 = 2.0;
 pub const DEFAULT_PERSISTENCE: f64 = 0.5;
+pub const DEFAULT_FRACTAL_MODE: FractalMode = FractalMode::Fbm;
+pub const DEFAULT_WARP_MODE: WarpMode = WarpMode::None;
+pub const DEFAULT_WARP_STRENGTH: f64 = 0.5;
+pub const DEFAULT_WARP_FREQUENCY: f64 = 2.0;
+pub const DEFAULT_RANGE_MODE: RangeMode = RangeMode::Normalized;
+pub const DEFAULT_EROSION_ITERATIONS: u64 = 0;
+pub const DEFAULT_EROSION_INERTIA: f64 = 0.05;
+pub const DEFAULT_SEDIMENT_CAPACITY: f64 = 4.0;
+pub const DEFAULT_DEPOSITION_RATE: f64 = 0.3;
+pub const DEFAULT_EROSION_RATE: f64 = 0.3;
+pub const DEFAULT_EVAPORATION: f64 = 0.01;
+pub const DEFAULT_THERMAL_ITERATIONS: u64 = 0;
+pub const DEFAULT_TALUS_ANGLE: f64 = 35.0;
+pub const DEFAULT_NEIGHBOURHOOD: Neighbourhood = Neighbourhood::Eight;
 pub const DEFAULT_COLOR_RAMP: [ColorRampStep; 9] = [
     ColorRampStep {
         color: Color([2, 43, 68, 255]),
@@ -50,6 +72,13 @@ pub const DEFAULT_COLOR_RAMP: [ColorRampStep; 9] = [
         position: 1.0,
     }, // white: snow
 ];
+pub const DEFAULT_ISLAND_MASK: IslandMask = IslandMask::Radial;
+pub const DEFAULT_ISLAND_CURVE: [(f64, f64); 5] =
+    [(0.0, 1.0), (0.5, 0.95), (0.75, 0.7), (0.9, 0.3), (1.0, 0.0)];
+pub const DEFAULT_ISLAND_CENTER_X: f64 = 0.5;
+pub const DEFAULT_ISLAND_CENTER_Y: f64 = 0.5;
+pub const DEFAULT_ISLAND_RADIUS: f64 = 0.5;
+pub const DEFAULT_ISLAND_STRENGTH: f64 = 0.6;
 pub const DEFAULT_FLATTEN: bool = true;
 pub const DEFAULT_USE_SHADING: bool = true;
 pub const DEFAULT_LIGHT: Color = Color([255, 255, 204, 255]);
@@ -0,0 +1,173 @@
+use super::{
+    heightmap::utils::Tiling,
+    parallel::{for_each_mut, min_max},
+};
+use bon::builder;
+use rand::Rng;
+
+const MAX_LIFETIME: usize = 30;
+const GRAVITY: f64 = 4.0;
+const MIN_CAPACITY: f64 = 0.01;
+
+/// Indices of the corners of the cell at (`x`, `y`) and the position in it,
+/// the cell wraps around the edges along the tiled axes.
+fn cell(width: usize, height: usize, tiling: Tiling, x: f64, y: f64) -> ([usize; 4], f64, f64) {
+    let xint = x as usize;
+    let yint = y as usize;
+    let next_x = if tiling.horizontal() {
+        (xint + 1) % width
+    } else {
+        xint + 1
+    };
+    let next_y = if tiling.vertical() {
+        (yint + 1) % height
+    } else {
+        yint + 1
+    };
+
+    (
+        [
+            xint + yint * width,
+            next_x + yint * width,
+            xint + next_y * width,
+            next_x + next_y * width,
+        ],
+        x - xint as f64,
+        y - yint as f64,
+    )
+}
+
+fn height_and_gradient(
+    heightmap: &[f64],
+    ([nw, ne, sw, se], u, v): ([usize; 4], f64, f64),
+) -> (f64, f64, f64) {
+    let (nw, ne, sw, se) = (heightmap[nw], heightmap[ne], heightmap[sw], heightmap[se]);
+
+    let gx = (ne - nw) * (1.0 - v) + (se - sw) * v;
+    let gy = (sw - nw) * (1.0 - u) + (se - ne) * u;
+    let h = nw * (1.0 - u) * (1.0 - v) + ne * u * (1.0 - v) + sw * (1.0 - u) * v + se * u * v;
+
+    (h, gx, gy)
+}
+
+fn spread(heightmap: &mut [f64], ([nw, ne, sw, se], u, v): ([usize; 4], f64, f64), amount: f64) {
+    heightmap[nw] += amount * (1.0 - u) * (1.0 - v);
+    heightmap[ne] += amount * u * (1.0 - v);
+    heightmap[sw] += amount * (1.0 - u) * v;
+    heightmap[se] += amount * u * v;
+}
+
+/// Moves `position` back into [0, `period`).
+fn wrap(position: f64, period: f64) -> f64 {
+    let position = position.rem_euclid(period);
+    // Tiny negative positions round up to the period.
+    if position >= period {
+        0.0
+    } else {
+        position
+    }
+}
+
+#[builder]
+pub fn hydraulic_erosion<R: Rng>(
+    mut heightmap: Vec<f64>,
+    width: usize,
+    height: usize,
+    iterations: u64,
+    inertia: f64,
+    capacity: f64,
+    deposition: f64,
+    erosion: f64,
+    evaporation: f64,
+    /// Droplets flow across the edges along the tiled axes.
+    #[builder(default)]
+    tiling: Tiling,
+    rng: &mut R,
+) -> Vec<f64> {
+    if iterations == 0 || width < 2 || height < 2 {
+        return heightmap;
+    }
+    let (min, max) = min_max(&heightmap).unwrap_or((0.0, 1.0));
+
+    // Positions span whole periods along the tiled axes, and stay inside the
+    // last cell along the others.
+    let max_x = if tiling.horizontal() {
+        width
+    } else {
+        width - 1
+    } as f64;
+    let max_y = if tiling.vertical() {
+        height
+    } else {
+        height - 1
+    } as f64;
+
+    for _ in 0..iterations {
+        let mut x = rng.gen_range(0.0..max_x);
+        let mut y = rng.gen_range(0.0..max_y);
+        let mut dx = 0.0;
+        let mut dy = 0.0;
+        let mut speed = 1.0;
+        let mut water = 1.0;
+        let mut sediment = 0.0;
+
+        for _ in 0..MAX_LIFETIME {
+            let (h, gx, gy) = height_and_gradient(&heightmap, cell(width, height, tiling, x, y));
+
+            dx = dx * inertia - gx * (1.0 - inertia);
+            dy = dy * inertia - gy * (1.0 - inertia);
+            let length = (dx * dx + dy * dy).sqrt();
+            if length == 0.0 {
+                break;
+            }
+            dx /= length;
+            dy /= length;
+
+            let (old_x, old_y) = (x, y);
+            x += dx;
+            y += dy;
+            if tiling.horizontal() {
+                x = wrap(x, max_x);
+            }
+            if tiling.vertical() {
+                y = wrap(y, max_y);
+            }
+            if x < 0.0 || x >= max_x || y < 0.0 || y >= max_y {
+                break;
+            }
+
+            let delta = height_and_gradient(&heightmap, cell(width, height, tiling, x, y)).0 - h;
+            let max_sediment = (-delta * speed * water * capacity).max(MIN_CAPACITY);
+
+            if delta > 0.0 || sediment > max_sediment {
+                let amount = if delta > 0.0 {
+                    delta.min(sediment)
+                } else {
+                    (sediment - max_sediment) * deposition
+                };
+                sediment -= amount;
+                spread(
+                    &mut heightmap,
+                    cell(width, height, tiling, old_x, old_y),
+                    amount,
+                );
+            } else {
+                let amount = ((max_sediment - sediment) * erosion).min(-delta);
+                sediment += amount;
+                spread(
+                    &mut heightmap,
+                    cell(width, height, tiling, old_x, old_y),
+                    -amount,
+                );
+            }
+
+            speed = (speed * speed - delta * GRAVITY).max(0.0).sqrt();
+            water *= 1.0 - evaporation;
+        }
+    }
+
+    // Kept within the range of the input rather than normalized, as Fixed
+    // range and imported heightmaps have the range the user chose.
+    for_each_mut(&mut heightmap, |value| *value = value.clamp(min, max));
+    heightmap
+}
@@ -0,0 +1,125 @@
+use super::{Generator, GeneratorType};
+use bon::bon;
+use png::{BitDepth, ColorType, Encoder, EncodingError};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum HeightmapFormat {
+    #[default]
+    #[strum(to_string = "PNG16")]
+    Png16,
+    #[strum(to_string = "R16")]
+    RawR16,
+    #[strum(to_string = "PFM")]
+    Pfm,
+}
+
+impl HeightmapFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            HeightmapFormat::Png16 => "png",
+            HeightmapFormat::RawR16 => "r16",
+            HeightmapFormat::Pfm => "pfm",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            HeightmapFormat::Png16 => "image/png",
+            HeightmapFormat::RawR16 | HeightmapFormat::Pfm => "application/octet-stream",
+        }
+    }
+}
+
+fn encode(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    color: ColorType,
+    depth: BitDepth,
+) -> Result<Vec<u8>, EncodingError> {
+    let mut png = Vec::new();
+
+    let mut encoder = Encoder::new(&mut png, width as u32, height as u32);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    encoder.write_header()?.write_image_data(data)?;
+
+    Ok(png)
+}
+
+pub fn encode_png(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>, EncodingError> {
+    encode(data, width, height, ColorType::Rgba, BitDepth::Eight)
+}
+
+#[bon]
+impl Generator {
+    pub fn export_png(&self, generator_type: GeneratorType) -> Result<Vec<u8>, EncodingError> {
+        let data = self.generate(generator_type);
+
+        if generator_type == GeneratorType::ColoredMap {
+            encode_png(&data, self.width, self.height)
+        } else {
+            let gray = data.iter().step_by(4).copied().collect::<Vec<_>>();
+            encode(
+                &gray,
+                self.width,
+                self.height,
+                ColorType::Grayscale,
+                BitDepth::Eight,
+            )
+        }
+    }
+
+    #[builder]
+    pub fn export_heightmap(
+        &self,
+        format: HeightmapFormat,
+        #[builder(default = 0.0)] min: f64,
+        #[builder(default = 1.0)] max: f64,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let heights = self
+            .flattened_map()
+            .iter()
+            .map(|&value| ((value - min) / (max - min)).clamp(0.0, 1.0));
+
+        match format {
+            HeightmapFormat::Png16 => {
+                let data = heights
+                    .flat_map(|value| ((value * 65535.0).round() as u16).to_be_bytes())
+                    .collect::<Vec<_>>();
+                encode(
+                    &data,
+                    self.width,
+                    self.height,
+                    ColorType::Grayscale,
+                    BitDepth::Sixteen,
+                )
+            }
+            HeightmapFormat::RawR16 => Ok(heights
+                .flat_map(|value| ((value * 65535.0).round() as u16).to_le_bytes())
+                .collect()),
+            HeightmapFormat::Pfm => {
+                let heights = heights.collect::<Vec<_>>();
+                let mut data = format!("Pf\n{} {}\n-1.0\n", self.width, self.height).into_bytes();
+
+                // PFM scanlines are stored from bottom to top.
+                for row in heights.chunks(self.width).rev() {
+                    data.extend(row.iter().flat_map(|&value| (value as f32).to_le_bytes()));
+                }
+
+                Ok(data)
+            }
+        }
+    }
+}
@@ -0,0 +1,1067 @@
+use super::{
+    erosion::hydraulic_erosion,
+    heightmap::{
+        utils::thermal_erosion, Fractal, FractalMode, Heightmap, HeightmapKind, Neighbourhood,
+        RangeMode, Tiling,
+    },
+    import::HeightmapImage,
+    interpolation::Interpolation,
+    island::{IslandCurve, IslandMask},
+    noise::{tiled_noise, CellReturn, Distance, DomainWarp, NoiseKind, NoiseType, WarpMode},
+    parallel::collect_rows,
+    settings::GeneratorSettings,
+    shade,
+    transform::{map_area, Transform},
+    Color, ColorRamp, Vec3,
+};
+use bon::Builder;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{cell::OnceCell, ops::Range};
+
+/// Pixels per noise lattice cell of the Noise view at a frequency of 1.
+const NOISE_VIEW_CELL_SIZE: f64 = 32.0;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum GeneratorType {
+    #[default]
+    Noise,
+    Heightmap,
+    ColoredMap,
+}
+
+#[derive(Builder)]
+pub struct Generator {
+    seed: u64,
+    interpolation: Interpolation,
+    noise: NoiseKind,
+    distance: Distance,
+    cell_return: CellReturn,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    heightmap: HeightmapKind,
+    pub(crate) tiling: Tiling,
+    image: Option<HeightmapImage>,
+    frequency: f64,
+    offset_x: f64,
+    offset_y: f64,
+    rotation: f64,
+    octave: u64,
+    lacunarity: f64,
+    persistence: f64,
+    fractal_mode: FractalMode,
+    warp_mode: WarpMode,
+    warp_strength: f64,
+    warp_frequency: f64,
+    range_mode: RangeMode,
+    erosion_iterations: u64,
+    erosion_inertia: f64,
+    sediment_capacity: f64,
+    deposition_rate: f64,
+    erosion_rate: f64,
+    evaporation: f64,
+    thermal_iterations: u64,
+    talus_angle: f64,
+    neighbourhood: Neighbourhood,
+    color_ramp: ColorRamp,
+    island_mask: IslandMask,
+    island_curve: IslandCurve,
+    island_center_x: f64,
+    island_center_y: f64,
+    island_radius: f64,
+    island_strength: f64,
+    flatten: bool,
+    use_shading: bool,
+    light_color: Color,
+    dark_color: Color,
+    light_position: Vec3,
+
+    #[builder(skip)]
+    current_noise: OnceCell<NoiseType>,
+    #[builder(skip)]
+    current_warp: OnceCell<DomainWarp>,
+    #[builder(skip)]
+    current_heightmap: OnceCell<Vec<f64>>,
+    #[builder(skip)]
+    current_hydraulic_map: OnceCell<Vec<f64>>,
+    #[builder(skip)]
+    current_thermal_map: OnceCell<Vec<f64>>,
+    #[builder(skip)]
+    current_flattened_map: OnceCell<Vec<f64>>,
+}
+
+impl Generator {
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.current_noise = OnceCell::new();
+        self.current_warp = OnceCell::new();
+        self.current_heightmap = OnceCell::new();
+        self.current_hydraulic_map = OnceCell::new();
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+
+        if self.noise != NoiseKind::Simplex && self.noise != NoiseKind::Worley {
+            self.current_noise = OnceCell::new();
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_noise(&mut self, noise: NoiseKind) {
+        self.noise = noise;
+        self.current_noise = OnceCell::new();
+        self.current_heightmap = OnceCell::new();
+        self.current_hydraulic_map = OnceCell::new();
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_distance(&mut self, distance: Distance) {
+        self.distance = distance;
+
+        if self.noise == NoiseKind::Worley {
+            self.current_noise = OnceCell::new();
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_cell_return(&mut self, cell_return: CellReturn) {
+        self.cell_return = cell_return;
+
+        if self.noise == NoiseKind::Worley {
+            self.current_noise = OnceCell::new();
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_width(&mut self, width: usize) {
+        self.width = width;
+        self.current_heightmap = OnceCell::new();
+        self.current_hydraulic_map = OnceCell::new();
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height;
+        self.current_heightmap = OnceCell::new();
+        self.current_hydraulic_map = OnceCell::new();
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_heightmap(&mut self, heightmap: HeightmapKind) {
+        self.heightmap = heightmap;
+        self.current_heightmap = OnceCell::new();
+        self.current_hydraulic_map = OnceCell::new();
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_tiling(&mut self, tiling: Tiling) {
+        self.tiling = tiling;
+        if self.heightmap != HeightmapKind::Image {
+            self.current_heightmap = OnceCell::new();
+        }
+        // Erosion wraps around the tiled edges.
+        if self.heightmap != HeightmapKind::Image
+            || self.erosion_iterations != 0
+            || self.thermal_iterations != 0
+        {
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_image(&mut self, image: Option<HeightmapImage>) {
+        self.image = image;
+        if self.heightmap == HeightmapKind::Image {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f64) {
+        self.frequency = frequency;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_offset_x(&mut self, offset_x: f64) {
+        self.offset_x = offset_x;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_offset_y(&mut self, offset_y: f64) {
+        self.offset_y = offset_y;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_rotation(&mut self, rotation: f64) {
+        self.rotation = rotation;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    /// Mapping from the map area to the noise domain.
+    pub(crate) fn transform(&self) -> Transform {
+        let (x_ratio, y_ratio) = map_area(self.width, self.height);
+
+        Transform {
+            frequency: self.frequency,
+            offset: (self.offset_x, self.offset_y),
+            // A rotated domain cannot wrap along the map edges.
+            rotation: if self.tiling == Tiling::None {
+                self.rotation
+            } else {
+                0.0
+            },
+            center: (x_ratio / 2.0, y_ratio / 2.0),
+        }
+    }
+
+    /// Transform of the Noise view, which shows the noise features at a
+    /// fixed pixel scale rather than stretched over the map area.
+    fn noise_view_transform(&self) -> Transform {
+        let scale = self.width.min(self.height) as f64 / NOISE_VIEW_CELL_SIZE;
+
+        Transform {
+            frequency: self.frequency * scale,
+            ..self.transform()
+        }
+    }
+
+    /// Transform of world-space chunks, rotated about the center of the unit
+    /// square rather than of the map so that chunks do not depend on the map
+    /// size. Chunks do not tile, so the rotation always applies.
+    pub(crate) fn world_transform(&self) -> Transform {
+        Transform {
+            rotation: self.rotation,
+            center: (0.5, 0.5),
+            ..self.transform()
+        }
+    }
+
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    pub fn offset(&self) -> (f64, f64) {
+        (self.offset_x, self.offset_y)
+    }
+
+    /// Noise domain coordinates sampled at the pixel (`x`, `y`) by the first
+    /// octave of the Fractal heightmap, before warping. The Noise view samples
+    /// the same points, scaled by a constant factor.
+    pub fn domain_position(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x_ratio, y_ratio) = map_area(self.width, self.height);
+        self.transform().apply(
+            x / self.width as f64 * x_ratio,
+            y / self.height as f64 * y_ratio,
+        )
+    }
+
+    /// Moves the view by (`dx`, `dy`) pixels, dragging the map along.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        let position = self.domain_position(0.0, 0.0);
+        self.anchor(position, dx, dy, self.frequency);
+    }
+
+    /// Multiplies the frequency by `factor`, keeping the pixel (`x`, `y`) on
+    /// the same point of the noise domain.
+    pub fn zoom(&mut self, factor: f64, x: f64, y: f64) {
+        let position = self.domain_position(x, y);
+        self.anchor(position, x, y, self.frequency * factor);
+    }
+
+    /// Sets the frequency, and the offset that maps the pixel (`x`, `y`) to `position`.
+    fn anchor(&mut self, position: (f64, f64), x: f64, y: f64, frequency: f64) {
+        self.frequency = frequency;
+        self.offset_x = 0.0;
+        self.offset_y = 0.0;
+        let origin = self.domain_position(x, y);
+
+        self.set_offset_x((position.0 - origin.0) / frequency);
+        self.set_offset_y((position.1 - origin.1) / frequency);
+    }
+
+    pub fn set_octave(&mut self, octave: u64) {
+        self.octave = octave;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_lacunarity(&mut self, lacunarity: f64) {
+        self.lacunarity = lacunarity;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_persistence(&mut self, persistence: f64) {
+        self.persistence = persistence;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_fractal_mode(&mut self, fractal_mode: FractalMode) {
+        self.fractal_mode = fractal_mode;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_warp_mode(&mut self, warp_mode: WarpMode) {
+        self.warp_mode = warp_mode;
+        self.current_warp = OnceCell::new();
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_warp_strength(&mut self, warp_strength: f64) {
+        self.warp_strength = warp_strength;
+        if self.warp_mode != WarpMode::None {
+            self.current_warp = OnceCell::new();
+            if self.heightmap == HeightmapKind::Fractal {
+                self.current_heightmap = OnceCell::new();
+                self.current_hydraulic_map = OnceCell::new();
+                self.current_thermal_map = OnceCell::new();
+                self.current_flattened_map = OnceCell::new();
+            }
+        }
+    }
+
+    pub fn set_warp_frequency(&mut self, warp_frequency: f64) {
+        self.warp_frequency = warp_frequency;
+        if self.warp_mode != WarpMode::None {
+            self.current_warp = OnceCell::new();
+            if self.heightmap == HeightmapKind::Fractal {
+                self.current_heightmap = OnceCell::new();
+                self.current_hydraulic_map = OnceCell::new();
+                self.current_thermal_map = OnceCell::new();
+                self.current_flattened_map = OnceCell::new();
+            }
+        }
+    }
+
+    pub fn set_range_mode(&mut self, range_mode: RangeMode) {
+        self.range_mode = range_mode;
+        if self.heightmap == HeightmapKind::Fractal {
+            self.current_heightmap = OnceCell::new();
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub(crate) fn warp(&self) -> &DomainWarp {
+        self.current_warp.get_or_init(|| {
+            // The warp fields get their own generator so that they do not
+            // change the noise drawn from the seed.
+            DomainWarp::new(
+                &mut StdRng::seed_from_u64(self.seed.wrapping_add(1)),
+                self.warp_mode,
+                self.warp_strength,
+                self.warp_frequency,
+            )
+        })
+    }
+
+    pub fn set_erosion_iterations(&mut self, erosion_iterations: u64) {
+        self.erosion_iterations = erosion_iterations;
+        self.current_hydraulic_map = OnceCell::new();
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_erosion_inertia(&mut self, erosion_inertia: f64) {
+        self.erosion_inertia = erosion_inertia;
+        if self.erosion_iterations != 0 {
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_sediment_capacity(&mut self, sediment_capacity: f64) {
+        self.sediment_capacity = sediment_capacity;
+        if self.erosion_iterations != 0 {
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_deposition_rate(&mut self, deposition_rate: f64) {
+        self.deposition_rate = deposition_rate;
+        if self.erosion_iterations != 0 {
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_erosion_rate(&mut self, erosion_rate: f64) {
+        self.erosion_rate = erosion_rate;
+        if self.erosion_iterations != 0 {
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_evaporation(&mut self, evaporation: f64) {
+        self.evaporation = evaporation;
+        if self.erosion_iterations != 0 {
+            self.current_hydraulic_map = OnceCell::new();
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_thermal_iterations(&mut self, thermal_iterations: u64) {
+        self.thermal_iterations = thermal_iterations;
+        self.current_thermal_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_talus_angle(&mut self, talus_angle: f64) {
+        self.talus_angle = talus_angle;
+        if self.thermal_iterations != 0 {
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) {
+        self.neighbourhood = neighbourhood;
+        if self.thermal_iterations != 0 {
+            self.current_thermal_map = OnceCell::new();
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_island_mask(&mut self, island_mask: IslandMask) {
+        self.island_mask = island_mask;
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_island_curve(&mut self, island_curve: IslandCurve) {
+        self.island_curve = island_curve;
+        if self.island_mask == IslandMask::Curve {
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_island_center_x(&mut self, island_center_x: f64) {
+        self.island_center_x = island_center_x;
+        if self.island_mask != IslandMask::None {
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_island_center_y(&mut self, island_center_y: f64) {
+        self.island_center_y = island_center_y;
+        if self.island_mask != IslandMask::None {
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_island_radius(&mut self, island_radius: f64) {
+        self.island_radius = island_radius;
+        if self.island_mask != IslandMask::None {
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_island_strength(&mut self, island_strength: f64) {
+        self.island_strength = island_strength;
+        if self.island_mask != IslandMask::None {
+            self.current_flattened_map = OnceCell::new();
+        }
+    }
+
+    pub fn set_flatten(&mut self, flatten: bool) {
+        self.flatten = flatten;
+        self.current_flattened_map = OnceCell::new();
+        self.current_flattened_map = OnceCell::new();
+    }
+
+    pub fn set_use_shading(&mut self, use_shading: bool) {
+        self.use_shading = use_shading;
+    }
+
+    /// Only the coloring uses the ramp, the cached maps are kept.
+    pub fn set_color_ramp(&mut self, color_ramp: ColorRamp) {
+        self.color_ramp = color_ramp;
+    }
+
+    /// Current settings, everything but the imported image.
+    pub fn settings(&self) -> GeneratorSettings {
+        GeneratorSettings {
+            seed: self.seed,
+            interpolation: self.interpolation,
+            noise: self.noise,
+            distance: self.distance,
+            cell_return: self.cell_return,
+            width: self.width,
+            height: self.height,
+            heightmap: self.heightmap,
+            tiling: self.tiling,
+            frequency: self.frequency,
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+            rotation: self.rotation,
+            octave: self.octave,
+            lacunarity: self.lacunarity,
+            persistence: self.persistence,
+            fractal_mode: self.fractal_mode,
+            warp_mode: self.warp_mode,
+            warp_strength: self.warp_strength,
+            warp_frequency: self.warp_frequency,
+            range_mode: self.range_mode,
+            erosion_iterations: self.erosion_iterations,
+            erosion_inertia: self.erosion_inertia,
+            sediment_capacity: self.sediment_capacity,
+            deposition_rate: self.deposition_rate,
+            erosion_rate: self.erosion_rate,
+            evaporation: self.evaporation,
+            thermal_iterations: self.thermal_iterations,
+            talus_angle: self.talus_angle,
+            neighbourhood: self.neighbourhood,
+            color_ramp: self.color_ramp.clone(),
+            island_mask: self.island_mask,
+            island_curve: self.island_curve.clone(),
+            island_center_x: self.island_center_x,
+            island_center_y: self.island_center_y,
+            island_radius: self.island_radius,
+            island_strength: self.island_strength,
+            flatten: self.flatten,
+            use_shading: self.use_shading,
+            light_color: self.light_color,
+            dark_color: self.dark_color,
+            light_position: self.light_position.clone(),
+        }
+    }
+
+    /// Applies `settings` through the setters of the changed values, so that
+    /// only the stages depending on them are generated again.
+    pub fn set_settings(&mut self, settings: GeneratorSettings) {
+        if settings.seed != self.seed {
+            self.set_seed(settings.seed);
+        }
+        if settings.interpolation != self.interpolation {
+            self.set_interpolation(settings.interpolation);
+        }
+        if settings.noise != self.noise {
+            self.set_noise(settings.noise);
+        }
+        if settings.distance != self.distance {
+            self.set_distance(settings.distance);
+        }
+        if settings.cell_return != self.cell_return {
+            self.set_cell_return(settings.cell_return);
+        }
+        if settings.width != self.width {
+            self.set_width(settings.width);
+        }
+        if settings.height != self.height {
+            self.set_height(settings.height);
+        }
+        if settings.heightmap != self.heightmap {
+            self.set_heightmap(settings.heightmap);
+        }
+        if settings.tiling != self.tiling {
+            self.set_tiling(settings.tiling);
+        }
+        if settings.frequency != self.frequency {
+            self.set_frequency(settings.frequency);
+        }
+        if settings.offset_x != self.offset_x {
+            self.set_offset_x(settings.offset_x);
+        }
+        if settings.offset_y != self.offset_y {
+            self.set_offset_y(settings.offset_y);
+        }
+        if settings.rotation != self.rotation {
+            self.set_rotation(settings.rotation);
+        }
+        if settings.octave != self.octave {
+            self.set_octave(settings.octave);
+        }
+        if settings.lacunarity != self.lacunarity {
+            self.set_lacunarity(settings.lacunarity);
+        }
+        if settings.persistence != self.persistence {
+            self.set_persistence(settings.persistence);
+        }
+        if settings.fractal_mode != self.fractal_mode {
+            self.set_fractal_mode(settings.fractal_mode);
+        }
+        if settings.warp_mode != self.warp_mode {
+            self.set_warp_mode(settings.warp_mode);
+        }
+        if settings.warp_strength != self.warp_strength {
+            self.set_warp_strength(settings.warp_strength);
+        }
+        if settings.warp_frequency != self.warp_frequency {
+            self.set_warp_frequency(settings.warp_frequency);
+        }
+        if settings.range_mode != self.range_mode {
+            self.set_range_mode(settings.range_mode);
+        }
+        if settings.erosion_iterations != self.erosion_iterations {
+            self.set_erosion_iterations(settings.erosion_iterations);
+        }
+        if settings.erosion_inertia != self.erosion_inertia {
+            self.set_erosion_inertia(settings.erosion_inertia);
+        }
+        if settings.sediment_capacity != self.sediment_capacity {
+            self.set_sediment_capacity(settings.sediment_capacity);
+        }
+        if settings.deposition_rate != self.deposition_rate {
+            self.set_deposition_rate(settings.deposition_rate);
+        }
+        if settings.erosion_rate != self.erosion_rate {
+            self.set_erosion_rate(settings.erosion_rate);
+        }
+        if settings.evaporation != self.evaporation {
+            self.set_evaporation(settings.evaporation);
+        }
+        if settings.thermal_iterations != self.thermal_iterations {
+            self.set_thermal_iterations(settings.thermal_iterations);
+        }
+        if settings.talus_angle != self.talus_angle {
+            self.set_talus_angle(settings.talus_angle);
+        }
+        if settings.neighbourhood != self.neighbourhood {
+            self.set_neighbourhood(settings.neighbourhood);
+        }
+        if settings.island_mask != self.island_mask {
+            self.set_island_mask(settings.island_mask);
+        }
+        if settings.island_curve != self.island_curve {
+            self.set_island_curve(settings.island_curve);
+        }
+        if settings.island_center_x != self.island_center_x {
+            self.set_island_center_x(settings.island_center_x);
+        }
+        if settings.island_center_y != self.island_center_y {
+            self.set_island_center_y(settings.island_center_y);
+        }
+        if settings.island_radius != self.island_radius {
+            self.set_island_radius(settings.island_radius);
+        }
+        if settings.island_strength != self.island_strength {
+            self.set_island_strength(settings.island_strength);
+        }
+        if settings.flatten != self.flatten {
+            self.set_flatten(settings.flatten);
+        }
+        if settings.use_shading != self.use_shading {
+            self.set_use_shading(settings.use_shading);
+        }
+
+        // Coloring and lighting are not cached.
+        self.set_color_ramp(settings.color_ramp);
+        self.light_color = settings.light_color;
+        self.dark_color = settings.dark_color;
+        self.light_position = settings.light_position;
+    }
+
+    pub fn flattened_map(&self) -> &[f64] {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let heightmap = self.current_heightmap.get_or_init(|| {
+            self.heightmap
+                .into()
+                .noise_cell(&self.current_noise)
+                .get_noise(|r| self.build_noise(r))
+                .octave(self.octave)
+                .lacunarity(self.lacunarity)
+                .persistance(self.persistence)
+                .mode(self.fractal_mode)
+                .warp(self.warp())
+                .range(self.range_mode)
+                .tiling(self.tiling)
+                .transform(self.transform())
+                .rng(&mut rng)
+                .maybe_image(self.image.as_ref())
+                .call()
+                .generate(self.width, self.height)
+        });
+
+        let heightmap = self.current_hydraulic_map.get_or_init(|| {
+            hydraulic_erosion()
+                .heightmap(heightmap.clone())
+                .width(self.width)
+                .height(self.height)
+                .iterations(self.erosion_iterations)
+                .inertia(self.erosion_inertia)
+                .capacity(self.sediment_capacity)
+                .deposition(self.deposition_rate)
+                .erosion(self.erosion_rate)
+                .evaporation(self.evaporation)
+                .tiling(self.tiling)
+                .rng(&mut StdRng::seed_from_u64(self.seed))
+                .call()
+        });
+
+        let heightmap = self.current_thermal_map.get_or_init(|| {
+            thermal_erosion(
+                heightmap.clone(),
+                self.width,
+                self.height,
+                self.talus_angle,
+                self.thermal_iterations,
+                self.neighbourhood,
+                self.tiling,
+            )
+        });
+
+        self.current_flattened_map.get_or_init(|| {
+            let heightmap = self
+                .island_mask
+                .apply()
+                .heightmap(heightmap)
+                .width(self.width)
+                .height(self.height)
+                .curve(&self.island_curve)
+                .center_x(self.island_center_x)
+                .center_y(self.island_center_y)
+                .radius(self.island_radius)
+                .strength(self.island_strength)
+                .call();
+
+            self.flatten_curve(heightmap)
+        })
+    }
+
+    pub(crate) fn flatten_curve(&self, heightmap: Vec<f64>) -> Vec<f64> {
+        if self.flatten {
+            heightmap
+                .iter()
+                .map(|&value| {
+                    (value - 0.5) * (value - 0.5) * if value < 0.5 { -2.0 } else { 2.0 } + 0.5
+                })
+                .collect()
+        } else {
+            heightmap
+        }
+    }
+
+    fn build_noise<R: Rng>(&self, rng: &mut R) -> NoiseType {
+        self.noise
+            .into()
+            .rng(rng)
+            .interpolation(self.interpolation)
+            .distance(self.distance)
+            .cell_return(self.cell_return)
+            .call()
+    }
+
+    pub(crate) fn noise_type(&self) -> &NoiseType {
+        self.current_noise
+            .get_or_init(|| self.build_noise(&mut StdRng::seed_from_u64(self.seed)))
+    }
+
+    /// Fractal heights at world-space points, in the fixed range whatever the range mode.
+    pub(crate) fn sample_fractal(&self, points: impl Iterator<Item = (f64, f64)>) -> Vec<f64> {
+        Fractal {
+            noise_cell: &self.current_noise,
+            get_noise: |r: &mut StdRng| self.build_noise(r),
+            octave: self.octave,
+            lacunarity: self.lacunarity,
+            persistance: self.persistence,
+            mode: self.fractal_mode,
+            warp: self.warp(),
+            range: RangeMode::Fixed,
+            tiling: Tiling::None,
+            transform: self.world_transform(),
+            rng: &mut StdRng::seed_from_u64(self.seed),
+        }
+        .sample(points)
+    }
+
+    /// Same generator at a `scale` times lower resolution, for previews. Its
+    /// hydraulic erosion drops fewer droplets to keep their density.
+    pub(crate) fn downscaled(&self, scale: usize) -> Generator {
+        let mut generator = Generator::from(self.settings());
+        generator.width = (self.width / scale).max(1);
+        generator.height = (self.height / scale).max(1);
+        generator.erosion_iterations = self.erosion_iterations / (scale * scale) as u64;
+        generator.image = self.image.clone();
+        generator
+    }
+
+    /// Whether the heightmap shown by `generator_type` is already generated.
+    pub(crate) fn is_cached(&self, generator_type: GeneratorType) -> bool {
+        generator_type != GeneratorType::Noise && self.current_flattened_map.get().is_some()
+    }
+
+    /// RGBA pixels of the `rows` of `heightmap`, whose shading still looks
+    /// at the neighbouring rows.
+    pub(crate) fn render(
+        &self,
+        heightmap: &[f64],
+        width: usize,
+        rows: Range<usize>,
+        generator_type: GeneratorType,
+    ) -> Vec<u8> {
+        // Rows run on several threads, which cannot share the caches of `self`.
+        let (color_ramp, tiling, use_shading) = (&self.color_ramp, self.tiling, self.use_shading);
+        let (light, light_color, dark_color) =
+            (&self.light_position, self.light_color, self.dark_color);
+
+        collect_rows(rows, |y| {
+            (y * width..(y + 1) * width).flat_map(move |index| {
+                let value = heightmap[index];
+
+                if generator_type != GeneratorType::ColoredMap {
+                    [(value * 255.0) as u8; 4]
+                } else if use_shading {
+                    *shade()
+                        .heightmap(heightmap)
+                        .index(index)
+                        .color(color_ramp.get(value))
+                        .width(width)
+                        .tiling(tiling)
+                        .light(light)
+                        .light_color(light_color)
+                        .dark_color(dark_color)
+                        .call()
+                } else {
+                    *color_ramp.get(value)
+                }
+            })
+        })
+    }
+
+    pub fn generate(&self, generator_type: GeneratorType) -> Vec<u8> {
+        self.generate_rows(generator_type, 0..self.height)
+    }
+
+    /// RGBA pixels of the `rows` of the map. The stages working on the whole
+    /// heightmap are still generated, and cached, at once.
+    pub fn generate_rows(&self, generator_type: GeneratorType, rows: Range<usize>) -> Vec<u8> {
+        if generator_type == GeneratorType::Noise {
+            let noise = self.noise_type();
+            let warp = self.warp();
+            let transform = self.noise_view_transform();
+            let (x_ratio, y_ratio) = map_area(self.width, self.height);
+            let (wf, hf) = (self.width as f64, self.height as f64);
+            let extent = transform.extent((
+                self.tiling.horizontal().then_some(x_ratio),
+                self.tiling.vertical().then_some(y_ratio),
+            ));
+
+            let width = self.width;
+
+            // Same sampling as the first octave of the Fractal heightmap, at
+            // the scale of the Noise view.
+            collect_rows(rows, |y| {
+                (0..width).flat_map(move |x| {
+                    let (x, y) =
+                        transform.apply((x as f64 / wf) * x_ratio, (y as f64 / hf) * y_ratio);
+                    let (x, y) = warp.apply(x, y, extent);
+                    let value = tiled_noise(noise, x, y, 1.0, extent);
+                    [(value * 255.0) as u8; 4]
+                })
+            })
+        } else {
+            self.render(self.flattened_map(), self.width, rows, generator_type)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::noise::tiled_noise;
+    use crate::{
+        Generator, GeneratorSettings, GeneratorType, HeightmapImage, HeightmapKind, IslandMask,
+        NoiseKind, RangeMode, Tiling,
+    };
+
+    const SIZE: usize = 64;
+
+    /// Mean height difference between columns `a` and `b`, or rows when
+    /// `transposed`.
+    fn difference(map: &[f64], a: usize, b: usize, transposed: bool) -> f64 {
+        let at = |i: usize, j: usize| {
+            if transposed {
+                map[i * SIZE + j]
+            } else {
+                map[j * SIZE + i]
+            }
+        };
+        (0..SIZE).map(|j| (at(a, j) - at(b, j)).abs()).sum::<f64>() / SIZE as f64
+    }
+
+    #[test]
+    fn tiled_maps_wrap_after_erosion() {
+        for noise in [NoiseKind::Gradient, NoiseKind::Simplex] {
+            let generator = Generator::from(GeneratorSettings {
+                noise,
+                width: SIZE,
+                height: SIZE,
+                tiling: Tiling::Both,
+                erosion_iterations: 2000,
+                thermal_iterations: 20,
+                island_mask: IslandMask::None,
+                ..Default::default()
+            });
+            let map = generator.flattened_map();
+
+            for transposed in [false, true] {
+                let interior = (0..SIZE - 1)
+                    .map(|i| difference(map, i, i + 1, transposed))
+                    .sum::<f64>()
+                    / (SIZE - 1) as f64;
+                let seam = difference(map, SIZE - 1, 0, transposed);
+                assert!(
+                    seam < 3.0 * interior,
+                    "{noise} seam {seam} against {interior} inside"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn noise_view_keeps_its_pixel_scale_at_the_defaults() {
+        let (width, height) = (96, 48);
+        let generator = Generator::from(GeneratorSettings {
+            width,
+            height,
+            ..Default::default()
+        });
+        let pixels = generator.generate(GeneratorType::Noise);
+
+        for (index, pixel) in pixels.chunks(4).enumerate() {
+            let (x, y) = ((index % width) as f64, (index / width) as f64);
+            let value = tiled_noise(
+                generator.noise_type(),
+                x / 32.0,
+                y / 32.0,
+                1.0,
+                (None, None),
+            );
+            assert_eq!(pixel[0], (value * 255.0) as u8, "pixel ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn erosion_keeps_the_range_of_the_heightmap() {
+        let generator = Generator::from(GeneratorSettings {
+            width: SIZE,
+            height: SIZE,
+            range_mode: RangeMode::Fixed,
+            erosion_iterations: 2000,
+            ..Default::default()
+        });
+        generator.flattened_map();
+
+        let range = |map: &[f64]| {
+            map.iter().fold((f64::MAX, f64::MIN), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            })
+        };
+        let (min, max) = range(generator.current_heightmap.get().unwrap());
+        let (eroded_min, eroded_max) = range(generator.current_hydraulic_map.get().unwrap());
+        assert!(max - min < 1.0, "the fixed range fills [0, 1]");
+        assert!(min <= eroded_min && eroded_max <= max);
+    }
+
+    #[test]
+    fn thermal_settings_keep_the_hydraulic_erosion() {
+        let mut generator = Generator::from(GeneratorSettings {
+            width: SIZE,
+            height: SIZE,
+            erosion_iterations: 2000,
+            thermal_iterations: 5,
+            ..Default::default()
+        });
+        generator.flattened_map();
+
+        generator.set_talus_angle(20.0);
+        assert!(generator.current_hydraulic_map.get().is_some());
+        assert!(generator.current_thermal_map.get().is_none());
+    }
+
+    #[test]
+    fn flat_images_give_flat_maps() {
+        let pgm = format!("P2\n4 4\n255\n{}", "128 ".repeat(16));
+        let mut generator = Generator::from(GeneratorSettings {
+            width: SIZE,
+            height: SIZE,
+            heightmap: HeightmapKind::Image,
+            erosion_iterations: 2000,
+            thermal_iterations: 5,
+            ..Default::default()
+        });
+        generator.set_image(Some(HeightmapImage::decode(pgm.as_bytes()).unwrap()));
+
+        assert!(generator.flattened_map().iter().all(|v| v.is_finite()));
+        generator.generate(GeneratorType::ColoredMap);
+    }
+}
@@ -1,18 +1,18 @@
 use super::{
-    utils::{normalize, submap},
+    utils::{grid_size, normalize, submap, wrap_edges, wrapped_submap, Tiling},
     Heightmap,
 };
 use rand::{
     distributions::{Distribution, Uniform},
     Rng,
 };
-use std::cmp::max;
 
 pub struct Diamond<'a, R>
 where
     R: Rng,
 {
     pub rng: &'a mut R,
+    pub tiling: Tiling,
 }
 
 impl<'a, R> Diamond<'a, R>
@@ -50,22 +50,36 @@ where
         let mut sum = 0.0;
         let mut count = 0.0;
         let idx = |x, y| x + y * size;
+        let period = size - 1;
 
+        // Along tiled axes, neighbours past an edge come from the opposite one.
         if x > 0 {
             sum += data[idx(x - d, y)];
             count += 1.0;
+        } else if self.tiling.horizontal() {
+            sum += data[idx(period - d, y)];
+            count += 1.0;
         }
         if x < size - 1 {
             sum += data[idx(x + d, y)];
             count += 1.0;
+        } else if self.tiling.horizontal() {
+            sum += data[idx(d, y)];
+            count += 1.0;
         }
         if y > 0 {
             sum += data[idx(x, y - d)];
             count += 1.0;
+        } else if self.tiling.vertical() {
+            sum += data[idx(x, period - d)];
+            count += 1.0;
         }
         if y < size - 1 {
             sum += data[idx(x, y + d)];
             count += 1.0;
+        } else if self.tiling.vertical() {
+            sum += data[idx(x, d)];
+            count += 1.0;
         }
 
         data[idx(x, y)] = sum / count + sampler.sample(self.rng);
@@ -78,7 +92,7 @@ where
 {
     fn generate(&mut self, width: usize, height: usize) -> Vec<f64> {
         let sampler = Uniform::from(0.0..=1.0);
-        let size = (max(width, height) - 1).next_power_of_two() + 1;
+        let size = grid_size(width, height, self.tiling);
         let mut data = vec![0.0; size * size];
         let idx = |x, y| x + y * size;
 
@@ -86,6 +100,7 @@ where
         data[idx(size - 1, 0)] = sampler.sample(self.rng);
         data[idx(0, size - 1)] = sampler.sample(self.rng);
         data[idx(size - 1, size - 1)] = sampler.sample(self.rng);
+        wrap_edges(&mut data, size, self.tiling);
 
         let mut d = size - 1;
         let mut delta = 0.5;
@@ -109,10 +124,15 @@ where
                 }
             }
 
+            wrap_edges(&mut data, size, self.tiling);
             d = half;
             delta /= 2.0;
         }
 
-        normalize(submap(size, size, width, height, data))
+        normalize(if self.tiling == Tiling::None {
+            submap(size, size, width, height, data)
+        } else {
+            wrapped_submap(size, width, height, self.tiling, data)
+        })
     }
 }
@@ -0,0 +1,277 @@
+use super::{
+    tiled_noise,
+    utils::{normalize, Tiling},
+    DomainWarp, Extent, Heightmap, NoiseType,
+};
+use crate::{
+    parallel::collect_rows,
+    transform::{map_area, Transform},
+};
+use rand::Rng;
+use std::cell::OnceCell;
+
+const RIDGED_OFFSET: f64 = 1.0;
+const RIDGED_GAIN: f64 = 2.0;
+const HYBRID_OFFSET: f64 = 0.7;
+const HETEROGENEOUS_OFFSET: f64 = 0.8;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum FractalMode {
+    #[default]
+    #[strum(to_string = "fBm")]
+    Fbm,
+    Ridged,
+    Billow,
+    Hybrid,
+    Heterogeneous,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum RangeMode {
+    /// Stretch each generated map to fill [0, 1].
+    #[default]
+    Normalized,
+    /// Map heights from the analytic bounds of the fractal, the same for every call.
+    Fixed,
+}
+
+pub struct Fractal<'a, R, F>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> NoiseType,
+{
+    pub noise_cell: &'a OnceCell<NoiseType>,
+    pub get_noise: F,
+    pub octave: u64,
+    pub lacunarity: f64,
+    pub persistance: f64,
+    pub mode: FractalMode,
+    pub warp: &'a DomainWarp,
+    pub range: RangeMode,
+    pub tiling: Tiling,
+    pub transform: Transform,
+    pub rng: &'a mut R,
+}
+
+/// Settings of the octaves, which rows sampled on several threads share.
+#[derive(Clone, Copy)]
+struct Octaves<'a> {
+    octave: u64,
+    lacunarity: f64,
+    persistance: f64,
+    mode: FractalMode,
+    warp: &'a DomainWarp,
+}
+
+impl Octaves<'_> {
+    fn get(&self, x: f64, y: f64, noise: &NoiseType, extent: Extent) -> f64 {
+        let (x, y) = self.warp.apply(x, y, extent);
+        let sample = |frequency: f64| tiled_noise(noise, x, y, frequency, extent);
+
+        // Noises are in [0, 1], the multifractals work on signed values.
+        let signed = |frequency: f64| sample(frequency) * 2.0 - 1.0;
+
+        let mut value = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut weight = 1.0;
+
+        for octave in 0..self.octave {
+            match self.mode {
+                FractalMode::Fbm => {
+                    value += sample(frequency) * amplitude;
+                }
+                FractalMode::Billow => {
+                    value += signed(frequency).abs() * amplitude;
+                }
+                FractalMode::Ridged => {
+                    let signal = (RIDGED_OFFSET - signed(frequency).abs()).powi(2) * weight;
+                    weight = (signal * RIDGED_GAIN).clamp(0.0, 1.0);
+                    value += signal * amplitude;
+                }
+                FractalMode::Hybrid => {
+                    let signal = (signed(frequency) + HYBRID_OFFSET) * amplitude;
+                    if octave == 0 {
+                        value = signal;
+                        weight = signal;
+                    } else {
+                        weight = weight.min(1.0);
+                        value += weight * signal;
+                        weight *= signal;
+                    }
+                }
+                FractalMode::Heterogeneous => {
+                    let signal = signed(frequency) + HETEROGENEOUS_OFFSET;
+                    if octave == 0 {
+                        value = signal;
+                    } else {
+                        // Clamped like the weight of the hybrid mode, which
+                        // keeps the bounds close to the heights.
+                        value += signal * amplitude * value.clamp(0.0, 1.0);
+                    }
+                }
+            }
+
+            frequency *= self.lacunarity;
+            amplitude *= self.persistance;
+        }
+
+        value
+    }
+
+    /// Bounds of `get`, from interval arithmetic over the same recurrences
+    /// with noises in [0, 1].
+    fn bounds(&self) -> (f64, f64) {
+        let add = |(a, b): (f64, f64), (c, d): (f64, f64)| (a + c, b + d);
+        let mul = |(a, b): (f64, f64), (c, d): (f64, f64)| {
+            let products = [a * c, a * d, b * c, b * d];
+            (
+                products.into_iter().fold(f64::MAX, f64::min),
+                products.into_iter().fold(f64::MIN, f64::max),
+            )
+        };
+
+        let mut value = (0.0, 0.0);
+        let mut amplitude = 1.0;
+        let mut weight = (1.0, 1.0);
+
+        for octave in 0..self.octave {
+            match self.mode {
+                FractalMode::Fbm | FractalMode::Billow => {
+                    value = add(value, (0.0, amplitude));
+                }
+                FractalMode::Ridged => {
+                    let base = (0.0, RIDGED_OFFSET.max(1.0 - RIDGED_OFFSET).powi(2));
+                    let signal = mul(base, weight);
+                    weight = (
+                        (signal.0 * RIDGED_GAIN).clamp(0.0, 1.0),
+                        (signal.1 * RIDGED_GAIN).clamp(0.0, 1.0),
+                    );
+                    value = add(value, mul(signal, (amplitude, amplitude)));
+                }
+                FractalMode::Hybrid => {
+                    let signal = (
+                        (HYBRID_OFFSET - 1.0) * amplitude,
+                        (HYBRID_OFFSET + 1.0) * amplitude,
+                    );
+                    if octave == 0 {
+                        value = signal;
+                        weight = signal;
+                    } else {
+                        weight = (weight.0.min(1.0), weight.1.min(1.0));
+                        value = add(value, mul(weight, signal));
+                        weight = mul(weight, signal);
+                    }
+                }
+                FractalMode::Heterogeneous => {
+                    let signal = (HETEROGENEOUS_OFFSET - 1.0, HETEROGENEOUS_OFFSET + 1.0);
+                    if octave == 0 {
+                        value = signal;
+                    } else {
+                        let scale = (value.0.clamp(0.0, 1.0), value.1.clamp(0.0, 1.0));
+                        value = add(value, mul(mul(signal, (amplitude, amplitude)), scale));
+                    }
+                }
+            }
+
+            amplitude *= self.persistance;
+        }
+
+        value
+    }
+}
+
+impl<'a, R, F> Fractal<'a, R, F>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> NoiseType,
+{
+    fn octaves(&self) -> Octaves<'a> {
+        Octaves {
+            octave: self.octave,
+            lacunarity: self.lacunarity,
+            persistance: self.persistance,
+            mode: self.mode,
+            warp: self.warp,
+        }
+    }
+
+    /// Samples the fractal at world-space points, with heights mapped from the
+    /// analytic bounds so that separate calls agree wherever their points do.
+    pub fn sample(&mut self, points: impl Iterator<Item = (f64, f64)>) -> Vec<f64> {
+        let noise = self.noise_cell.get_or_init(|| (self.get_noise)(self.rng));
+        let octaves = self.octaves();
+        self.fixed(points.map(|(x, y)| {
+            let (x, y) = self.transform.apply(x, y);
+            octaves.get(x, y, noise, (None, None))
+        }))
+    }
+
+    fn fixed(&self, heightmap: impl Iterator<Item = f64>) -> Vec<f64> {
+        let (min, max) = self.octaves().bounds();
+
+        heightmap
+            .map(|value| {
+                if max > min {
+                    ((value - min) / (max - min)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'a, R, F> Heightmap for Fractal<'a, R, F>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> NoiseType,
+{
+    fn generate(&mut self, width: usize, height: usize) -> Vec<f64> {
+        let wf = width as f64;
+        let hf = height as f64;
+        let (x_ratio, y_ratio) = map_area(width, height);
+        let transform = self.transform;
+
+        // Tiled maps wrap over the area they span in the noise domain.
+        let extent = self.transform.extent((
+            self.tiling.horizontal().then_some(x_ratio),
+            self.tiling.vertical().then_some(y_ratio),
+        ));
+        let noise = self.noise_cell.get_or_init(|| (self.get_noise)(self.rng));
+        let octaves = self.octaves();
+        let heightmap = collect_rows(0..height, |y| {
+            (0..width).map(move |x| {
+                let (x, y) = transform.apply((x as f64 / wf) * x_ratio, (y as f64 / hf) * y_ratio);
+                octaves.get(x, y, noise, extent)
+            })
+        });
+
+        match self.range {
+            RangeMode::Normalized => normalize(heightmap),
+            RangeMode::Fixed => self.fixed(heightmap.into_iter()),
+        }
+    }
+}
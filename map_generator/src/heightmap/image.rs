@@ -0,0 +1,15 @@
+use super::{utils::normalize, Heightmap};
+use crate::HeightmapImage;
+
+pub struct Image<'a> {
+    pub image: Option<&'a HeightmapImage>,
+}
+
+impl<'a> Heightmap for Image<'a> {
+    fn generate(&mut self, width: usize, height: usize) -> Vec<f64> {
+        match self.image {
+            Some(image) => normalize(image.resample(width, height)),
+            None => vec![0.0; width * height],
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use super::noise::{Noise, NoiseType};
+use super::{
+    noise::{tiled_noise, DomainWarp, Extent, NoiseType},
+    transform::Transform,
+    HeightmapImage,
+};
 use bon::bon;
 use enum_dispatch::enum_dispatch;
 use rand::Rng;
@@ -6,8 +10,13 @@ use std::cell::OnceCell;
 
 mod diamond;
 mod fractal;
+mod image;
 mod midpoint;
-mod utils;
+pub mod utils;
+
+pub(crate) use fractal::Fractal;
+pub use fractal::{FractalMode, RangeMode};
+pub use utils::{Neighbourhood, Tiling};
 
 #[enum_dispatch]
 pub trait Heightmap {
@@ -23,14 +32,27 @@ where
     Midpoint(midpoint::Midpoint<'a, R>),
     Diamond(diamond::Diamond<'a, R>),
     Fractal(fractal::Fractal<'a, R, F>),
+    Image(image::Image<'a>),
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumIter)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum HeightmapKind {
     #[default]
     Midpoint,
     Diamond,
     Fractal,
+    Image,
 }
 
 #[bon]
@@ -43,24 +65,36 @@ impl HeightmapKind {
         octave: u64,
         lacunarity: f64,
         persistance: f64,
+        mode: FractalMode,
+        warp: &'a DomainWarp,
+        range: RangeMode,
+        tiling: Tiling,
+        transform: Transform,
         rng: &'a mut R,
+        image: Option<&'a HeightmapImage>,
     ) -> HeightmapType<'a, R, F>
     where
         R: Rng,
         F: FnMut(&mut R) -> NoiseType,
     {
         match self {
-            HeightmapKind::Midpoint => midpoint::Midpoint { rng }.into(),
-            HeightmapKind::Diamond => diamond::Diamond { rng }.into(),
+            HeightmapKind::Midpoint => midpoint::Midpoint { rng, tiling }.into(),
+            HeightmapKind::Diamond => diamond::Diamond { rng, tiling }.into(),
             HeightmapKind::Fractal => fractal::Fractal {
                 noise_cell,
                 get_noise,
                 octave,
                 lacunarity,
                 persistance,
+                mode,
+                warp,
+                range,
+                tiling,
+                transform,
                 rng,
             }
             .into(),
+            HeightmapKind::Image => image::Image { image }.into(),
         }
     }
 }
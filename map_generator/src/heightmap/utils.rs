@@ -0,0 +1,252 @@
+use crate::parallel::{for_each_mut, min_max};
+
+pub fn submap(
+    current_width: usize,
+    current_height: usize,
+    target_width: usize,
+    target_height: usize,
+    data: Vec<f64>,
+) -> Vec<f64> {
+    if current_width == target_width && current_height == target_height {
+        data
+    } else {
+        data.into_iter()
+            .take(current_width * target_height)
+            .enumerate()
+            .filter_map(|(i, v)| {
+                if i % current_width < target_width {
+                    Some(v)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Tiling {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl Tiling {
+    pub fn horizontal(self) -> bool {
+        matches!(self, Tiling::Horizontal | Tiling::Both)
+    }
+
+    pub fn vertical(self) -> bool {
+        matches!(self, Tiling::Vertical | Tiling::Both)
+    }
+}
+
+/// Side of the square grid the subdivision generators fill for a target size.
+/// Untiled grids span the target with a power of two plus one points, tiled
+/// grids are a torus of a power of two period, resampled afterwards.
+pub fn grid_size(width: usize, height: usize, tiling: Tiling) -> usize {
+    let side = width.max(height);
+    if tiling == Tiling::None {
+        (side - 1).next_power_of_two() + 1
+    } else {
+        side.next_power_of_two() + 1
+    }
+}
+
+/// Resamples a `size` by `size` grid whose last row and column repeat the
+/// first ones, so that the target wraps along the tiled axes. Other axes are
+/// cropped like `submap`.
+pub fn wrapped_submap(
+    size: usize,
+    target_width: usize,
+    target_height: usize,
+    tiling: Tiling,
+    data: Vec<f64>,
+) -> Vec<f64> {
+    let period = (size - 1) as f64;
+    let axis = |i: usize, target: usize, wraps: bool| {
+        if wraps {
+            let position = i as f64 * period / target as f64;
+            (position as usize, position.fract())
+        } else {
+            (i, 0.0)
+        }
+    };
+    let get = |x: usize, y: usize| data[x.min(size - 1) + y.min(size - 1) * size];
+
+    (0..target_height)
+        .flat_map(|y| (0..target_width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (xint, u) = axis(x, target_width, tiling.horizontal());
+            let (yint, v) = axis(y, target_height, tiling.vertical());
+
+            let n = get(xint, yint) * (1.0 - u) + get(xint + 1, yint) * u;
+            let s = get(xint, yint + 1) * (1.0 - u) + get(xint + 1, yint + 1) * u;
+            n * (1.0 - v) + s * v
+        })
+        .collect()
+}
+
+/// Copies the first row and column of a `size` by `size` grid over the last
+/// ones along the tiled axes.
+pub fn wrap_edges(data: &mut [f64], size: usize, tiling: Tiling) {
+    for i in 0..size {
+        if tiling.horizontal() {
+            data[size - 1 + i * size] = data[i * size];
+        }
+        if tiling.vertical() {
+            data[i + (size - 1) * size] = data[i];
+        }
+    }
+}
+
+/// Stretches the heightmap to fill [0, 1], flat heightmaps becoming 0.
+pub fn normalize(mut heightmap: Vec<f64>) -> Vec<f64> {
+    if let Some((min, max)) = min_max(&heightmap) {
+        if max > min {
+            for_each_mut(&mut heightmap, |val| {
+                *val = (*val - min) / (max - min);
+            });
+        } else {
+            for_each_mut(&mut heightmap, |val| *val = 0.0);
+        }
+    };
+
+    heightmap
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Neighbourhood {
+    #[default]
+    #[strum(to_string = "4 neighbours")]
+    Four,
+    #[strum(to_string = "8 neighbours")]
+    Eight,
+}
+
+impl Neighbourhood {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighbourhood::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+fn neighbour_coordinate(i: usize, offset: isize, size: usize, wraps: bool) -> Option<usize> {
+    if wraps {
+        Some((i as isize + offset).rem_euclid(size as isize) as usize)
+    } else {
+        i.checked_add_signed(offset).filter(|&n| n < size)
+    }
+}
+
+pub fn thermal_erosion(
+    mut heightmap: Vec<f64>,
+    width: usize,
+    height: usize,
+    talus_angle: f64,
+    iterations: u64,
+    neighbourhood: Neighbourhood,
+    tiling: Tiling,
+) -> Vec<f64> {
+    if iterations == 0 {
+        return heightmap;
+    }
+
+    // The map spans a unit square horizontally and a unit range vertically,
+    // so the talus height difference scales with the cell size.
+    let talus = talus_angle.to_radians().tan() / width.max(height) as f64;
+    let offsets = neighbourhood.offsets();
+    let mut delta = vec![0.0; heightmap.len()];
+
+    for _ in 0..iterations {
+        for y in 0..height {
+            for x in 0..width {
+                let index = x + y * width;
+                let h = heightmap[index];
+
+                let mut max_diff = 0.0;
+                let mut total_diff = 0.0;
+                let mut excess = [(0, 0.0); 8];
+                let mut count = 0;
+
+                for &(dx, dy) in offsets {
+                    // Neighbours wrap around the edges along the tiled axes.
+                    let (Some(nx), Some(ny)) = (
+                        neighbour_coordinate(x, dx, width, tiling.horizontal()),
+                        neighbour_coordinate(y, dy, height, tiling.vertical()),
+                    ) else {
+                        continue;
+                    };
+
+                    let neighbour = nx + ny * width;
+                    let distance = if dx != 0 && dy != 0 {
+                        std::f64::consts::SQRT_2
+                    } else {
+                        1.0
+                    };
+                    let diff = h - heightmap[neighbour] - talus * distance;
+
+                    if diff > 0.0 {
+                        excess[count] = (neighbour, diff);
+                        count += 1;
+                        total_diff += diff;
+                        max_diff = f64::max(max_diff, diff);
+                    }
+                }
+
+                if count > 0 {
+                    let moved = max_diff / 2.0;
+                    delta[index] -= moved;
+                    for &(neighbour, diff) in &excess[..count] {
+                        delta[neighbour] += moved * diff / total_diff;
+                    }
+                }
+            }
+        }
+
+        heightmap
+            .iter_mut()
+            .zip(delta.iter_mut())
+            .for_each(|(h, d)| {
+                *h += *d;
+                *d = 0.0;
+            });
+    }
+
+    heightmap
+}
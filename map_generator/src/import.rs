@@ -0,0 +1,173 @@
+use png::{BitDepth, ColorType, Decoder, DecodingError, Transformations};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Png(DecodingError),
+    Pgm(&'static str),
+    UnknownFormat,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Png(e) => write!(f, "invalid PNG image: {e}"),
+            ImportError::Pgm(e) => write!(f, "invalid PGM image: {e}"),
+            ImportError::UnknownFormat => write!(f, "expected a PNG or PGM image"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<DecodingError> for ImportError {
+    fn from(e: DecodingError) -> Self {
+        ImportError::Png(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeightmapImage {
+    width: usize,
+    height: usize,
+    data: Vec<f64>,
+}
+
+impl HeightmapImage {
+    pub fn decode(bytes: &[u8]) -> Result<Self, ImportError> {
+        if bytes.starts_with(b"\x89PNG") {
+            Self::decode_png(bytes)
+        } else if bytes.starts_with(b"P5") || bytes.starts_with(b"P2") {
+            Self::decode_pgm(bytes)
+        } else {
+            Err(ImportError::UnknownFormat)
+        }
+    }
+
+    fn decode_png(bytes: &[u8]) -> Result<Self, ImportError> {
+        let mut decoder = Decoder::new(bytes);
+        decoder.set_transformations(Transformations::EXPAND);
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let buffer = &buffer[..info.buffer_size()];
+
+        let samples = match info.bit_depth {
+            BitDepth::Sixteen => buffer
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as f64 / 65535.0)
+                .collect::<Vec<_>>(),
+            _ => buffer.iter().map(|&b| b as f64 / 255.0).collect(),
+        };
+
+        // Colored images are reduced to their mean intensity, alpha is ignored.
+        let (channels, colors) = match info.color_type {
+            ColorType::Grayscale => (1, 1),
+            ColorType::GrayscaleAlpha => (2, 1),
+            ColorType::Rgb => (3, 3),
+            ColorType::Rgba => (4, 3),
+            ColorType::Indexed => unreachable!("EXPAND converts indexed images"),
+        };
+
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            data: samples
+                .chunks_exact(channels)
+                .map(|pixel| pixel[..colors].iter().sum::<f64>() / colors as f64)
+                .collect(),
+        })
+    }
+
+    fn decode_pgm(bytes: &[u8]) -> Result<Self, ImportError> {
+        let mut header = Vec::with_capacity(4);
+        let mut position = 2;
+
+        while header.len() < 3 {
+            match bytes.get(position) {
+                Some(b'#') => {
+                    while bytes.get(position).is_some_and(|&b| b != b'\n') {
+                        position += 1;
+                    }
+                }
+                Some(b) if b.is_ascii_whitespace() => position += 1,
+                Some(b) if b.is_ascii_digit() => {
+                    let start = position;
+                    while bytes.get(position).is_some_and(u8::is_ascii_digit) {
+                        position += 1;
+                    }
+                    let value = std::str::from_utf8(&bytes[start..position])
+                        .ok()
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .ok_or(ImportError::Pgm("invalid header"))?;
+                    header.push(value);
+                }
+                _ => return Err(ImportError::Pgm("invalid header")),
+            }
+        }
+
+        let (width, height, max) = (header[0], header[1], header[2]);
+        if max == 0 || max > 65535 {
+            return Err(ImportError::Pgm("invalid maximum value"));
+        }
+        let size = width
+            .checked_mul(height)
+            .ok_or(ImportError::Pgm("image too large"))?;
+
+        let data = if bytes.starts_with(b"P5") {
+            // A single whitespace separates the header from the raster.
+            let raster = bytes.get(position + 1..).unwrap_or_default();
+            if max < 256 {
+                raster.iter().map(|&b| b as f64 / max as f64).collect()
+            } else {
+                raster
+                    .chunks_exact(2)
+                    .map(|b| u16::from_be_bytes([b[0], b[1]]) as f64 / max as f64)
+                    .collect::<Vec<_>>()
+            }
+        } else {
+            std::str::from_utf8(&bytes[position..])
+                .map_err(|_| ImportError::Pgm("invalid raster"))?
+                .split_ascii_whitespace()
+                .map(|s| s.parse::<usize>().map(|v| v as f64 / max as f64))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ImportError::Pgm("invalid raster"))?
+        };
+
+        if data.len() < size {
+            return Err(ImportError::Pgm("truncated raster"));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data: data[..size].to_vec(),
+        })
+    }
+
+    pub fn resample(&self, width: usize, height: usize) -> Vec<f64> {
+        if self.width == 0 || self.height == 0 {
+            return vec![0.0; width * height];
+        }
+
+        let x_ratio = (self.width - 1) as f64 / (width.max(2) - 1) as f64;
+        let y_ratio = (self.height - 1) as f64 / (height.max(2) - 1) as f64;
+        let get = |x: usize, y: usize| {
+            self.data[x.min(self.width - 1) + y.min(self.height - 1) * self.width]
+        };
+
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let sx = x as f64 * x_ratio;
+                let sy = y as f64 * y_ratio;
+                let (xint, yint) = (sx as usize, sy as usize);
+                let (u, v) = (sx.fract(), sy.fract());
+
+                let n = get(xint, yint) * (1.0 - u) + get(xint + 1, yint) * u;
+                let s = get(xint, yint + 1) * (1.0 - u) + get(xint + 1, yint + 1) * u;
+                n * (1.0 - v) + s * v
+            })
+            .collect()
+    }
+}
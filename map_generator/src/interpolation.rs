@@ -20,7 +20,18 @@ fn cosine(a: f64, b: f64, t: f64) -> f64 {
     lerp(a, b, f)
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumIter)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum Interpolation {
     #[default]
     Linear,
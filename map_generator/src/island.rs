@@ -0,0 +1,153 @@
+use bon::bon;
+use std::{fmt, str::FromStr};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum IslandMask {
+    #[default]
+    None,
+    Radial,
+    SquaredDistance,
+    RoundedRectangle,
+    Curve,
+}
+
+/// Serialized as its list of points, which gets sorted again when read.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Vec<(f64, f64)>", into = "Vec<(f64, f64)>")]
+pub struct IslandCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl From<Vec<(f64, f64)>> for IslandCurve {
+    fn from(mut points: Vec<(f64, f64)>) -> Self {
+        IslandCurve {
+            points: {
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                points
+            },
+        }
+    }
+}
+
+impl From<IslandCurve> for Vec<(f64, f64)> {
+    fn from(curve: IslandCurve) -> Self {
+        curve.points
+    }
+}
+
+/// Written as `distance:height` points separated by commas, as in `0:1, 1:0`.
+impl fmt::Display for IslandCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (distance, height)) in self.points.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{distance}:{height}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for IslandCurve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|point| {
+                let (distance, height) = point
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected a distance:height point: {point}"))?;
+                let parse = |s: &str| s.trim().parse::<f64>().map_err(|e| e.to_string());
+                Ok((parse(distance)?, parse(height)?))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(IslandCurve::from)
+    }
+}
+
+impl IslandCurve {
+    pub fn get(&self, distance: f64) -> f64 {
+        match self.points.iter().position(|x| x.0 >= distance) {
+            Some(0) => self.points[0].1,
+            Some(i) => {
+                let a = self.points[i - 1];
+                let b = self.points[i];
+                let t = (distance - a.0) / (b.0 - a.0);
+                a.1 + (b.1 - a.1) * t
+            }
+            None => self.points.last().map_or(0.0, |p| p.1),
+        }
+    }
+}
+
+/// Width of the ring along the edges where heights are capped, as a fraction
+/// of the distance from the edges to the center.
+const BORDER_WIDTH: f64 = 0.1;
+
+#[bon]
+impl IslandMask {
+    fn falloff(self, x: f64, y: f64, radius: f64, curve: &IslandCurve) -> f64 {
+        let radius = radius.max(f64::EPSILON);
+
+        match self {
+            IslandMask::None => 1.0,
+            IslandMask::Radial => 1.0 - ((x * x + y * y).sqrt() / radius).min(1.0),
+            IslandMask::SquaredDistance => 1.0 - ((x * x + y * y) / (radius * radius)).min(1.0),
+            IslandMask::RoundedRectangle => {
+                1.0 - ((x.powi(4) + y.powi(4)).sqrt().sqrt() / radius).min(1.0)
+            }
+            IslandMask::Curve => curve.get((x * x + y * y).sqrt() / radius).clamp(0.0, 1.0),
+        }
+    }
+
+    #[builder]
+    pub fn apply(
+        self,
+        heightmap: &[f64],
+        width: usize,
+        height: usize,
+        curve: &IslandCurve,
+        center_x: f64,
+        center_y: f64,
+        radius: f64,
+        strength: f64,
+    ) -> Vec<f64> {
+        if self == IslandMask::None {
+            return heightmap.to_vec();
+        }
+
+        let wf = (width.max(2) - 1) as f64;
+        let hf = (height.max(2) - 1) as f64;
+
+        heightmap
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| {
+                let u = (index % width) as f64 / wf;
+                let v = (index / width) as f64 / hf;
+
+                // The border term reaches zero on every edge, whatever the
+                // center and radius, so the map is always surrounded by water.
+                let border = 1.0 - (2.0 * u - 1.0).abs().max((2.0 * v - 1.0).abs());
+                let mask = self
+                    .falloff(u - center_x, v - center_y, radius, curve)
+                    .min(border);
+
+                // Capped after the blend, so that the outer ring stays below
+                // the sea level of 0.5 whatever the strength.
+                (value * (1.0 - strength) + mask * strength).min(border / BORDER_WIDTH)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,42 @@
+mod chunk;
+mod color;
+pub mod defaults;
+mod erosion;
+mod export;
+mod generator;
+mod heightmap;
+mod import;
+mod interpolation;
+mod island;
+mod mesh;
+mod noise;
+mod parallel;
+mod presets;
+mod progressive;
+mod settings;
+mod shader;
+mod transform;
+mod vec3;
+
+pub use color::{lerp, Color, ColorRamp, ColorRampStep};
+pub use erosion::hydraulic_erosion;
+pub use export::{encode_png, HeightmapFormat};
+pub use generator::{Generator, GeneratorType};
+pub use heightmap::{
+    utils::{normalize, thermal_erosion},
+    FractalMode, Heightmap, HeightmapKind, HeightmapType, Neighbourhood, RangeMode, Tiling,
+};
+pub use import::{HeightmapImage, ImportError};
+pub use interpolation::Interpolation;
+pub use island::{IslandCurve, IslandMask};
+pub use mesh::{obj_material, MeshColors, MeshFormat};
+pub use noise::{CellReturn, Distance, DomainWarp, Noise, NoiseKind, NoiseType, WarpMode};
+pub use presets::ColorRampPreset;
+pub use progressive::Rows;
+pub use settings::{
+    GeneratorSettings, SettingsError, SettingsFormat, MAX_EROSION_ITERATIONS, MAX_OCTAVE, MAX_SIZE,
+    MAX_THERMAL_ITERATIONS, MIN_SIZE, SETTINGS_VERSION,
+};
+pub use shader::{normal, shade};
+pub use transform::{map_area, Transform};
+pub use vec3::Vec3;
@@ -0,0 +1,388 @@
+use super::{export::encode_png, shader::normal, Generator, GeneratorType};
+use bon::bon;
+use png::EncodingError;
+use std::fmt::Write;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum MeshFormat {
+    #[default]
+    #[strum(to_string = "glTF")]
+    Glb,
+    #[strum(to_string = "OBJ")]
+    Obj,
+}
+
+impl MeshFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            MeshFormat::Glb => "glb",
+            MeshFormat::Obj => "obj",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            MeshFormat::Glb => "model/gltf-binary",
+            MeshFormat::Obj => "model/obj",
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum MeshColors {
+    #[default]
+    #[strum(to_string = "Vertex colors")]
+    VertexColors,
+    Texture,
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn pad(buffer: &mut Vec<u8>, byte: u8) {
+    while !buffer.len().is_multiple_of(4) {
+        buffer.push(byte);
+    }
+}
+
+pub fn obj_material(name: &str) -> Vec<u8> {
+    format!("newmtl {name}\nKa 1 1 1\nKd 1 1 1\nmap_Kd {name}.png\n").into_bytes()
+}
+
+struct Grid {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+#[bon]
+impl Generator {
+    fn grid(&self, spacing: f64, vertical_scale: f64) -> Grid {
+        let heightmap = self.flattened_map();
+        let (width, height) = (self.width, self.height);
+        let u_max = (width.max(2) - 1) as f32;
+        let v_max = (height.max(2) - 1) as f32;
+
+        let positions = heightmap
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| {
+                [
+                    ((index % width) as f64 * spacing) as f32,
+                    (value * vertical_scale) as f32,
+                    ((index / width) as f64 * spacing) as f32,
+                ]
+            })
+            .collect();
+
+        // Same normals as the shading, with the image y axis mapped to the mesh z axis.
+        let normals = (0..heightmap.len())
+            .map(|index| {
+                let n = normal(
+                    heightmap,
+                    index,
+                    width,
+                    vertical_scale / spacing,
+                    self.tiling,
+                );
+                [n.0[0] as f32, n.0[2] as f32, n.0[1] as f32]
+            })
+            .collect();
+
+        let uvs = (0..heightmap.len())
+            .map(|index| {
+                [
+                    (index % width) as f32 / u_max,
+                    (index / width) as f32 / v_max,
+                ]
+            })
+            .collect();
+
+        let indices = (0..height.saturating_sub(1))
+            .flat_map(|y| (0..width.saturating_sub(1)).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let a = (x + y * width) as u32;
+                let b = a + 1;
+                let c = a + width as u32;
+                let d = c + 1;
+                [a, c, b, b, c, d]
+            })
+            .collect();
+
+        Grid {
+            positions,
+            normals,
+            uvs,
+            indices,
+        }
+    }
+
+    #[builder]
+    pub fn export_mesh(
+        &self,
+        format: MeshFormat,
+        colors: MeshColors,
+        #[builder(default = 1.0)] spacing: f64,
+        #[builder(default = 64.0)] vertical_scale: f64,
+        #[builder(default = "map")] name: &str,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let grid = self.grid(spacing, vertical_scale);
+
+        match format {
+            MeshFormat::Obj => Ok(self.obj(&grid, colors, name)),
+            MeshFormat::Glb => self.glb(&grid, colors),
+        }
+    }
+
+    fn obj(&self, grid: &Grid, colors: MeshColors, name: &str) -> Vec<u8> {
+        let mut obj = String::new();
+
+        if colors == MeshColors::Texture {
+            writeln!(obj, "mtllib {name}.mtl").unwrap();
+        }
+
+        if colors == MeshColors::VertexColors {
+            let rgba = self.generate(GeneratorType::ColoredMap);
+            for (p, c) in grid.positions.iter().zip(rgba.chunks_exact(4)) {
+                writeln!(
+                    obj,
+                    "v {} {} {} {} {} {}",
+                    p[0],
+                    p[1],
+                    p[2],
+                    c[0] as f32 / 255.0,
+                    c[1] as f32 / 255.0,
+                    c[2] as f32 / 255.0
+                )
+                .unwrap();
+            }
+        } else {
+            for p in &grid.positions {
+                writeln!(obj, "v {} {} {}", p[0], p[1], p[2]).unwrap();
+            }
+        }
+
+        for n in &grid.normals {
+            writeln!(obj, "vn {} {} {}", n[0], n[1], n[2]).unwrap();
+        }
+        // OBJ texture coordinates start at the bottom left corner.
+        for uv in &grid.uvs {
+            writeln!(obj, "vt {} {}", uv[0], 1.0 - uv[1]).unwrap();
+        }
+
+        if colors == MeshColors::Texture {
+            writeln!(obj, "usemtl {name}").unwrap();
+        }
+        for face in grid.indices.chunks_exact(3) {
+            let [a, b, c] = [face[0] + 1, face[1] + 1, face[2] + 1];
+            writeln!(obj, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}").unwrap();
+        }
+
+        obj.into_bytes()
+    }
+
+    fn glb(&self, grid: &Grid, colors: MeshColors) -> Result<Vec<u8>, EncodingError> {
+        let mut bin = Vec::new();
+        let mut views = Vec::new();
+        let mut push_view = |bin: &mut Vec<u8>, data: &[u8], target: Option<u32>| {
+            pad(bin, 0);
+            let view = match target {
+                Some(target) => format!(
+                    r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{target}}}"#,
+                    bin.len(),
+                    data.len()
+                ),
+                None => format!(
+                    r#"{{"buffer":0,"byteOffset":{},"byteLength":{}}}"#,
+                    bin.len(),
+                    data.len()
+                ),
+            };
+            bin.extend_from_slice(data);
+            views.push(view);
+            views.len() - 1
+        };
+
+        const ARRAY_BUFFER: Option<u32> = Some(34962);
+        const ELEMENT_ARRAY_BUFFER: Option<u32> = Some(34963);
+        const FLOAT: u32 = 5126;
+        const UNSIGNED_INT: u32 = 5125;
+
+        let floats = |values: &mut dyn Iterator<Item = f32>| {
+            values.flat_map(f32::to_le_bytes).collect::<Vec<_>>()
+        };
+
+        let vertices = grid.positions.len();
+        let (min, max) =
+            grid.positions
+                .iter()
+                .fold(([f32::MAX; 3], [f32::MIN; 3]), |(mut min, mut max), p| {
+                    for i in 0..3 {
+                        min[i] = min[i].min(p[i]);
+                        max[i] = max[i].max(p[i]);
+                    }
+                    (min, max)
+                });
+
+        let position = push_view(
+            &mut bin,
+            &floats(&mut grid.positions.iter().flatten().copied()),
+            ARRAY_BUFFER,
+        );
+        let normal = push_view(
+            &mut bin,
+            &floats(&mut grid.normals.iter().flatten().copied()),
+            ARRAY_BUFFER,
+        );
+        let uv = push_view(
+            &mut bin,
+            &floats(&mut grid.uvs.iter().flatten().copied()),
+            ARRAY_BUFFER,
+        );
+        let indices = push_view(
+            &mut bin,
+            &grid
+                .indices
+                .iter()
+                .flat_map(|i| i.to_le_bytes())
+                .collect::<Vec<_>>(),
+            ELEMENT_ARRAY_BUFFER,
+        );
+
+        let mut accessors = vec![
+            format!(
+                r#"{{"bufferView":{position},"componentType":{FLOAT},"count":{vertices},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+                min[0], min[1], min[2], max[0], max[1], max[2]
+            ),
+            format!(
+                r#"{{"bufferView":{normal},"componentType":{FLOAT},"count":{vertices},"type":"VEC3"}}"#
+            ),
+            format!(
+                r#"{{"bufferView":{uv},"componentType":{FLOAT},"count":{vertices},"type":"VEC2"}}"#
+            ),
+            format!(
+                r#"{{"bufferView":{indices},"componentType":{UNSIGNED_INT},"count":{},"type":"SCALAR"}}"#,
+                grid.indices.len()
+            ),
+        ];
+
+        let rgba = self.generate(GeneratorType::ColoredMap);
+        let (attributes, material, textures) = match colors {
+            MeshColors::VertexColors => {
+                let color = push_view(
+                    &mut bin,
+                    &floats(&mut rgba.iter().enumerate().map(|(i, &c)| {
+                        if i % 4 == 3 {
+                            c as f32 / 255.0
+                        } else {
+                            srgb_to_linear(c)
+                        }
+                    })),
+                    ARRAY_BUFFER,
+                );
+                accessors.push(format!(
+                    r#"{{"bufferView":{color},"componentType":{FLOAT},"count":{vertices},"type":"VEC4"}}"#
+                ));
+                (
+                    r#""POSITION":0,"NORMAL":1,"TEXCOORD_0":2,"COLOR_0":4"#,
+                    r#"{"pbrMetallicRoughness":{"metallicFactor":0,"roughnessFactor":1}}"#,
+                    String::new(),
+                )
+            }
+            MeshColors::Texture => {
+                let image = push_view(&mut bin, &encode_png(&rgba, self.width, self.height)?, None);
+                (
+                    r#""POSITION":0,"NORMAL":1,"TEXCOORD_0":2"#,
+                    r#"{"pbrMetallicRoughness":{"baseColorTexture":{"index":0},"metallicFactor":0,"roughnessFactor":1}}"#,
+                    format!(
+                        r#","images":[{{"bufferView":{image},"mimeType":"image/png"}}],"samplers":[{{"magFilter":9729,"minFilter":9729,"wrapS":33071,"wrapT":33071}}],"textures":[{{"sampler":0,"source":0}}]"#
+                    ),
+                )
+            }
+        };
+        pad(&mut bin, 0);
+
+        let mut json = format!(
+            r#"{{"asset":{{"version":"2.0","generator":"map-generator"}},"scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"meshes":[{{"primitives":[{{"attributes":{{{attributes}}},"indices":3,"material":0}}]}}],"materials":[{material}]{textures},"accessors":[{}],"bufferViews":[{}],"buffers":[{{"byteLength":{}}}]}}"#,
+            accessors.join(","),
+            views.join(","),
+            bin.len()
+        )
+        .into_bytes();
+        pad(&mut json, b' ');
+
+        let mut glb = Vec::with_capacity(28 + json.len() + bin.len());
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&((28 + json.len() + bin.len()) as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&bin);
+
+        Ok(glb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Generator, GeneratorSettings, MeshColors, MeshFormat, Tiling};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn single_rows_and_columns_export() {
+        for ((width, height), tiling) in [(1, 8), (8, 1), (1, 1)]
+            .into_iter()
+            .flat_map(|size| [(size, Tiling::None), (size, Tiling::Both)])
+        {
+            let generator = Generator::from(GeneratorSettings {
+                width,
+                height,
+                tiling,
+                ..Default::default()
+            });
+
+            for (format, colors) in MeshFormat::iter().zip(MeshColors::iter()) {
+                let mesh = generator
+                    .export_mesh()
+                    .format(format)
+                    .colors(colors)
+                    .vertical_scale(16.0)
+                    .name("Mesh")
+                    .call();
+                assert!(mesh.is_ok(), "{width}x{height} {tiling} {format} mesh");
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use core::f64;
 
-use super::Noise;
-use crate::map_generator::Interpolation;
+use super::{lattice, next, Noise, Period};
+use crate::Interpolation;
 use rand::{
     distributions::{Distribution, Uniform},
     seq::SliceRandom,
@@ -62,18 +62,15 @@ impl Gradient {
 }
 
 impl Noise for Gradient {
-    fn noise(&self, x: f64, y: f64) -> f64 {
-        let xint = x as usize;
-        let yint = y as usize;
-
-        let xf = x.fract();
-        let yf = y.fract();
+    fn periodic_noise(&self, x: f64, y: f64, (px, py): Period) -> f64 {
+        let (xint, xf) = lattice(x, px);
+        let (yint, yf) = lattice(y, py);
+        let (xnext, ynext) = (next(xint, px), next(yint, py));
 
         let nw = self.gradients[self.index(xint, yint)].dot(Vector::new(xf, yf));
-        let ne = self.gradients[self.index(xint + 1, yint)].dot(Vector::new(xf - 1.0, yf));
-        let sw = self.gradients[self.index(xint, yint + 1)].dot(Vector::new(xf, yf - 1.0));
-        let se =
-            self.gradients[self.index(xint + 1, yint + 1)].dot(Vector::new(xf - 1.0, yf - 1.0));
+        let ne = self.gradients[self.index(xnext, yint)].dot(Vector::new(xf - 1.0, yf));
+        let sw = self.gradients[self.index(xint, ynext)].dot(Vector::new(xf, yf - 1.0));
+        let se = self.gradients[self.index(xnext, ynext)].dot(Vector::new(xf - 1.0, yf - 1.0));
 
         let n = (self.interpolation)(nw, ne, xf);
         let s = (self.interpolation)(sw, se, xf);
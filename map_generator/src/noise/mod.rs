@@ -0,0 +1,109 @@
+use crate::Interpolation;
+use bon::bon;
+use enum_dispatch::enum_dispatch;
+use rand::Rng;
+
+mod gradient;
+mod simplex;
+mod value;
+mod warp;
+mod worley;
+
+pub use warp::{DomainWarp, WarpMode};
+pub use worley::{CellReturn, Distance};
+
+/// Lattice period of a noise along each axis, `None` when it does not wrap.
+pub type Period = (Option<usize>, Option<usize>);
+
+/// Size of the sampled area along each axis, for the axes that must wrap.
+pub type Extent = (Option<f64>, Option<f64>);
+
+/// Splits a coordinate into its lattice cell, wrapped to the `period` or to the
+/// 256 entries of the permutation tables, and its offset inside that cell.
+///
+/// Flooring keeps the offset in [0, 1) for negative coordinates, and wrapping
+/// before the conversion keeps the cell valid over the whole f64 range.
+fn lattice(x: f64, period: Option<usize>) -> (usize, f64) {
+    let floor = x.floor();
+    let cells = period.map_or(256.0, |period| period as f64);
+    (floor.rem_euclid(cells) as usize, x - floor)
+}
+
+fn next(cell: usize, period: Option<usize>) -> usize {
+    period.map_or(cell + 1, |period| (cell + 1) % period)
+}
+
+#[enum_dispatch]
+pub trait Noise {
+    fn noise(&self, x: f64, y: f64) -> f64 {
+        self.periodic_noise(x, y, (None, None))
+    }
+
+    fn periodic_noise(&self, x: f64, y: f64, period: Period) -> f64;
+}
+
+/// Samples `noise` at `frequency`, rounded along the axes of `extent` to a
+/// whole number of lattice cells so that the noise wraps over the extent.
+pub fn tiled_noise(noise: &NoiseType, x: f64, y: f64, frequency: f64, extent: Extent) -> f64 {
+    let axis = |c: f64, extent: Option<f64>| match extent {
+        Some(extent) => {
+            let period = (extent * frequency).round().max(1.0);
+            (c * period / extent, Some(period as usize))
+        }
+        None => (c * frequency, None),
+    };
+    let (x, px) = axis(x, extent.0);
+    let (y, py) = axis(y, extent.1);
+
+    noise.periodic_noise(x, y, (px, py))
+}
+
+#[enum_dispatch(Noise)]
+#[allow(clippy::large_enum_variant)]
+pub enum NoiseType {
+    Value(value::Value),
+    Gradient(gradient::Gradient),
+    Simplex(simplex::Simplex),
+    Worley(worley::Worley),
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum NoiseKind {
+    #[default]
+    Value,
+    Gradient,
+    /// Tiles by blending copies shifted by the period rather than with a
+    /// periodic lattice, see `Simplex::periodic_noise`.
+    Simplex,
+    Worley,
+}
+
+#[bon]
+impl NoiseKind {
+    #[builder]
+    pub fn into<R: Rng>(
+        self,
+        rng: &mut R,
+        interpolation: Interpolation,
+        distance: Distance,
+        cell_return: CellReturn,
+    ) -> NoiseType {
+        match self {
+            NoiseKind::Value => value::Value::new(rng, interpolation).into(),
+            NoiseKind::Gradient => gradient::Gradient::new(rng, interpolation).into(),
+            NoiseKind::Simplex => simplex::Simplex::new(rng).into(),
+            NoiseKind::Worley => worley::Worley::new(rng, distance, cell_return).into(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::Noise;
+use super::{lattice, Noise, Period};
 use rand::{seq::SliceRandom, Rng};
 
 fn grad(hash: u8, x: f64, y: f64) -> f64 {
@@ -33,20 +33,19 @@ impl Simplex {
     fn index(&self, x: usize, y: usize) -> u8 {
         self.permutation[(self.permutation[x & 0xFF] as usize + y) & 0xFF]
     }
-}
 
-impl Noise for Simplex {
-    fn noise(&self, x: f64, y: f64) -> f64 {
+    fn raw(&self, x: f64, y: f64) -> f64 {
         let f2 = 0.5 * (f64::sqrt(3.0) - 1.0);
         let g2 = (3.0 - f64::sqrt(3.0)) / 6.0;
 
         let s = (x + y) * f2;
-        let i = (x + s) as usize;
-        let j = (y + s) as usize;
+        let (i, xs) = lattice(x + s, None);
+        let (j, ys) = lattice(y + s, None);
 
-        let t = ((i + j) as f64) * g2;
-        let x0 = x - (i as f64) + t;
-        let y0 = y - (j as f64) + t;
+        // Unskewing the offsets directly keeps them precise far from the origin.
+        let t = (xs + ys) * g2;
+        let x0 = xs - t;
+        let y0 = ys - t;
 
         let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
 
@@ -88,3 +87,39 @@ impl Noise for Simplex {
         35.0 * (n0 + n1 + n2) + 0.5
     }
 }
+
+impl Noise for Simplex {
+    /// The skewed lattice cannot line up with a rectangular period, so tiled
+    /// simplex noise blends copies shifted by the period instead of hashing a
+    /// periodic lattice. Blending independent copies flattens the middle of
+    /// the tile, which the variance of the weights corrects for.
+    fn periodic_noise(&self, x: f64, y: f64, (px, py): Period) -> f64 {
+        if px.is_none() && py.is_none() {
+            return self.raw(x, y);
+        }
+
+        let axis = |c: f64, period: Option<usize>| match period {
+            Some(period) => {
+                let period = period as f64;
+                let c = c.rem_euclid(period);
+                [(c, 1.0 - c / period), (c - period, c / period)]
+            }
+            None => [(c, 1.0), (c, 0.0)],
+        };
+
+        let mut deviation = 0.0;
+        let mut variance = 0.0;
+        for (y, wy) in axis(y, py) {
+            for (x, wx) in axis(x, px) {
+                let weight = wx * wy;
+                if weight > 0.0 {
+                    deviation += weight * (self.raw(x, y) - 0.5);
+                    variance += weight * weight;
+                }
+            }
+        }
+
+        // Clamped so that noises stay in [0, 1] for the fractal bounds.
+        (0.5 + deviation / variance.sqrt()).clamp(0.0, 1.0)
+    }
+}
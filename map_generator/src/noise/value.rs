@@ -1,5 +1,5 @@
-use super::Noise;
-use crate::map_generator::Interpolation;
+use super::{lattice, next, Noise, Period};
+use crate::Interpolation;
 use rand::{
     distributions::{Distribution, Uniform},
     seq::SliceRandom,
@@ -39,17 +39,15 @@ impl Value {
 }
 
 impl Noise for Value {
-    fn noise(&self, x: f64, y: f64) -> f64 {
-        let xint = x as usize;
-        let yint = y as usize;
+    fn periodic_noise(&self, x: f64, y: f64, (px, py): Period) -> f64 {
+        let (xint, xf) = lattice(x, px);
+        let (yint, yf) = lattice(y, py);
+        let (xnext, ynext) = (next(xint, px), next(yint, py));
 
         let nw = self.values[self.index(xint, yint)];
-        let ne = self.values[self.index(xint + 1, yint)];
-        let sw = self.values[self.index(xint, yint + 1)];
-        let se = self.values[self.index(xint + 1, yint + 1)];
-
-        let xf = x.fract();
-        let yf = y.fract();
+        let ne = self.values[self.index(xnext, yint)];
+        let sw = self.values[self.index(xint, ynext)];
+        let se = self.values[self.index(xnext, ynext)];
 
         let n = (self.interpolation)(nw, ne, xf);
         let s = (self.interpolation)(sw, se, xf);
@@ -0,0 +1,95 @@
+use super::{gradient::Gradient, tiled_noise, Extent, NoiseType};
+use crate::Interpolation;
+use rand::Rng;
+
+const OCTAVES: u64 = 3;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum WarpMode {
+    #[default]
+    None,
+    Single,
+    Iterated,
+}
+
+pub struct DomainWarp {
+    mode: WarpMode,
+    strength: f64,
+    frequency: f64,
+    fields: Vec<NoiseType>,
+}
+
+impl DomainWarp {
+    pub fn new<R: Rng>(rng: &mut R, mode: WarpMode, strength: f64, frequency: f64) -> Self {
+        let count = match mode {
+            WarpMode::None => 0,
+            WarpMode::Single => 2,
+            WarpMode::Iterated => 4,
+        };
+
+        Self {
+            mode,
+            strength,
+            frequency,
+            fields: (0..count)
+                .map(|_| Gradient::new(rng, Interpolation::Quintic).into())
+                .collect(),
+        }
+    }
+
+    fn field(&self, index: usize, x: f64, y: f64, extent: Extent) -> f64 {
+        let mut value = 0.0;
+        let mut frequency = self.frequency;
+        let mut amplitude = 0.5;
+
+        for _ in 0..OCTAVES {
+            value +=
+                (tiled_noise(&self.fields[index], x, y, frequency, extent) * 2.0 - 1.0) * amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+
+        value
+    }
+
+    /// Warped coordinates, with offsets that wrap over the axes of `extent`.
+    pub fn apply(&self, x: f64, y: f64, extent: Extent) -> (f64, f64) {
+        match self.mode {
+            WarpMode::None => (x, y),
+            WarpMode::Single => (
+                x + self.strength * self.field(0, x, y, extent),
+                y + self.strength * self.field(1, x, y, extent),
+            ),
+            // f(p + h(p + g(p))), as described by Inigo Quilez.
+            WarpMode::Iterated => {
+                let qx = self.field(0, x, y, extent);
+                let qy = self.field(1, x + 5.2, y + 1.3, extent);
+                let rx = self.field(
+                    2,
+                    x + self.strength * qx + 1.7,
+                    y + self.strength * qy + 9.2,
+                    extent,
+                );
+                let ry = self.field(
+                    3,
+                    x + self.strength * qx + 8.3,
+                    y + self.strength * qy + 2.8,
+                    extent,
+                );
+
+                (x + self.strength * rx, y + self.strength * ry)
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use super::{lattice, Noise, Period};
+use rand::{
+    distributions::{Distribution, Uniform},
+    seq::SliceRandom,
+    Rng,
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Distance {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Distance {
+    fn get(self, dx: f64, dy: f64) -> f64 {
+        match self {
+            Distance::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Distance::Manhattan => dx.abs() + dy.abs(),
+            Distance::Chebyshev => dx.abs().max(dy.abs()),
+        }
+    }
+
+    // Upper bound of F2 with one feature point per cell and a 3x3 search,
+    // used to bring the distances back into [0, 1].
+    fn range(self) -> f64 {
+        match self {
+            Distance::Euclidean => 1.5,
+            Distance::Manhattan => 2.0,
+            Distance::Chebyshev => 1.0,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum CellReturn {
+    #[default]
+    F1,
+    F2,
+    #[strum(to_string = "F2 - F1")]
+    F2MinusF1,
+    #[strum(to_string = "Cell value")]
+    CellValue,
+}
+
+pub struct Worley {
+    permutation: [u8; 256],
+    points: [(f64, f64); 256],
+    values: [f64; 256],
+    distance: Distance,
+    cell_return: CellReturn,
+}
+
+impl Worley {
+    pub fn new<R: Rng>(rng: &mut R, distance: Distance, cell_return: CellReturn) -> Self {
+        let mut permutation = [0; 256];
+        for (i, x) in permutation.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        permutation.shuffle(rng);
+
+        let distribution = Uniform::from(0.0..1.0);
+        let mut points = [(0.0, 0.0); 256];
+        for p in points.iter_mut() {
+            *p = (distribution.sample(rng), distribution.sample(rng));
+        }
+
+        let distribution = Uniform::from(0.0..=1.0);
+        let mut values = [0.0; 256];
+        for v in values.iter_mut() {
+            *v = distribution.sample(rng);
+        }
+
+        Self {
+            permutation,
+            points,
+            values,
+            distance,
+            cell_return,
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        self.permutation[(self.permutation[x & 0xFF] as usize + (y & 0xFF)) & 0xFF] as usize
+    }
+}
+
+impl Noise for Worley {
+    fn periodic_noise(&self, x: f64, y: f64, (px, py): Period) -> f64 {
+        let (xint, xf) = lattice(x, px);
+        let (yint, yf) = lattice(y, py);
+        let neighbour = |cell: usize, offset: isize, period: Option<usize>| match period {
+            Some(period) => (cell + period).wrapping_add_signed(offset) % period,
+            None => cell.wrapping_add_signed(offset),
+        };
+
+        let mut f1 = f64::MAX;
+        let mut f2 = f64::MAX;
+        let mut nearest = 0;
+
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let index = self.index(neighbour(xint, dx, px), neighbour(yint, dy, py));
+                let (px, py) = self.points[index];
+                let d = self.distance.get(dx as f64 + px - xf, dy as f64 + py - yf);
+
+                if d < f1 {
+                    f2 = f1;
+                    f1 = d;
+                    nearest = index;
+                } else if d < f2 {
+                    f2 = d;
+                }
+            }
+        }
+
+        let range = self.distance.range();
+        match self.cell_return {
+            CellReturn::F1 => (f1 / range).min(1.0),
+            CellReturn::F2 => (f2 / range).min(1.0),
+            CellReturn::F2MinusF1 => ((f2 - f1) / range).min(1.0),
+            CellReturn::CellValue => self.values[nearest],
+        }
+    }
+}
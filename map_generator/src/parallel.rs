@@ -0,0 +1,126 @@
+//! Loops that run on all threads with the `parallel` feature. Each value is
+//! computed the same way on any thread, so the results are identical.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Concatenates the values of each row, in order.
+pub(crate) fn collect_rows<T, I, F>(rows: Range<usize>, row: F) -> Vec<T>
+where
+    T: Send,
+    I: Iterator<Item = T>,
+    F: Fn(usize) -> I + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        rows.into_par_iter().flat_map_iter(row).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        rows.flat_map(row).collect()
+    }
+}
+
+pub(crate) fn for_each_mut<T, F>(values: &mut [T], f: F)
+where
+    T: Send,
+    F: Fn(&mut T) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        values.par_iter_mut().for_each(f)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        values.iter_mut().for_each(f)
+    }
+}
+
+pub(crate) fn min_max(values: &[f64]) -> Option<(f64, f64)> {
+    let first = *values.first()?;
+    let fold = |(min, max): (f64, f64), (a, b): (f64, f64)| (min.min(a), max.max(b));
+
+    #[cfg(feature = "parallel")]
+    {
+        Some(
+            values
+                .par_iter()
+                .map(|&value| (value, value))
+                .reduce(|| (first, first), fold),
+        )
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        Some(
+            values
+                .iter()
+                .map(|&value| (value, value))
+                .fold((first, first), fold),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_rows, for_each_mut, min_max};
+
+    /// Runs `f` on more threads than the machine may have cores, so that
+    /// rayon splits the work. Without the `parallel` feature, runs it as is.
+    fn on_threads<T>(f: impl FnOnce() -> T + Send) -> T
+    where
+        T: Send,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(8)
+                .build()
+                .expect("Failed to build the thread pool")
+                .install(f)
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            f()
+        }
+    }
+
+    fn values() -> Vec<f64> {
+        (0..10_000)
+            .map(|i| (i as f64 * 0.37).sin() * i as f64)
+            .collect()
+    }
+
+    #[test]
+    fn rows_are_collected_in_order() {
+        let row = |y: usize| (0..100).map(move |x| x * y);
+        let expected = (3..200).flat_map(row).collect::<Vec<_>>();
+
+        assert_eq!(on_threads(|| collect_rows(3..200, row)), expected);
+    }
+
+    #[test]
+    fn every_value_is_mapped() {
+        let mut values = values();
+        let expected = values
+            .iter()
+            .map(|value| value * 2.0 - 1.0)
+            .collect::<Vec<_>>();
+        on_threads(|| for_each_mut(&mut values, |value| *value = *value * 2.0 - 1.0));
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn min_max_matches_a_sequential_fold() {
+        let values = values();
+        let expected = values
+            .iter()
+            .fold((values[0], values[0]), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+
+        assert_eq!(on_threads(|| min_max(&values)), Some(expected));
+        assert_eq!(on_threads(|| min_max(&[])), None);
+    }
+}
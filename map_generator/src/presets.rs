@@ -0,0 +1,158 @@
+use crate::{defaults::DEFAULT_COLOR_RAMP, Color, ColorRamp, ColorRampStep};
+
+/// Built-in color ramps. Ramps with water put the shore at 0.5, like the
+/// default one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumIter)]
+pub enum ColorRampPreset {
+    #[default]
+    #[strum(to_string = "Temperate island")]
+    TemperateIsland,
+    Desert,
+    Arctic,
+    Volcanic,
+    Alien,
+    Grayscale,
+    Bathymetric,
+    #[strum(to_string = "USGS hypsometric tint")]
+    Hypsometric,
+    #[strum(to_string = "Colorblind safe")]
+    ColorblindSafe,
+}
+
+impl ColorRampPreset {
+    pub fn ramp(self) -> ColorRamp {
+        match self {
+            ColorRampPreset::TemperateIsland => ColorRamp::temperate_island(),
+            ColorRampPreset::Desert => ColorRamp::desert(),
+            ColorRampPreset::Arctic => ColorRamp::arctic(),
+            ColorRampPreset::Volcanic => ColorRamp::volcanic(),
+            ColorRampPreset::Alien => ColorRamp::alien(),
+            ColorRampPreset::Grayscale => ColorRamp::grayscale(),
+            ColorRampPreset::Bathymetric => ColorRamp::bathymetric(),
+            ColorRampPreset::Hypsometric => ColorRamp::hypsometric(),
+            ColorRampPreset::ColorblindSafe => ColorRamp::colorblind_safe(),
+        }
+    }
+}
+
+fn opaque(steps: &[([u8; 3], f64)]) -> ColorRamp {
+    ColorRamp::from(
+        steps
+            .iter()
+            .map(|&([r, g, b], position)| ColorRampStep {
+                color: Color([r, g, b, 255]),
+                position,
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+impl ColorRamp {
+    /// The default ramp: water, grass, tundra, rocks and snow.
+    pub fn temperate_island() -> Self {
+        ColorRamp::from(DEFAULT_COLOR_RAMP.to_vec())
+    }
+
+    /// Oases in sand dunes under red rocks.
+    pub fn desert() -> Self {
+        opaque(&[
+            ([12, 70, 84], 0.0),     // dark teal: deep water
+            ([38, 128, 128], 0.47),  // teal: oasis
+            ([84, 128, 64], 0.5),    // green: palms
+            ([222, 192, 134], 0.52), // pale sand: beach
+            ([214, 168, 102], 0.7),  // sand: dunes
+            ([184, 124, 76], 0.85),  // sandstone
+            ([142, 76, 52], 1.0),    // red rock
+        ])
+    }
+
+    /// Sea ice, snowfields and bare rocks.
+    pub fn arctic() -> Self {
+        opaque(&[
+            ([10, 30, 56], 0.0),     // very dark blue: deep water
+            ([40, 84, 120], 0.4),    // dark blue: water
+            ([150, 196, 220], 0.49), // light blue: sea ice
+            ([232, 240, 246], 0.51), // white: snow
+            ([200, 210, 220], 0.75), // blue grey: glacier
+            ([120, 124, 130], 0.88), // grey: rocks
+            ([250, 252, 255], 1.0),  // white: summits
+        ])
+    }
+
+    /// Black sand, basalt and ash around glowing peaks.
+    pub fn volcanic() -> Self {
+        opaque(&[
+            ([8, 24, 36], 0.0),    // very dark blue: deep water
+            ([24, 52, 66], 0.48),  // dark blue: water
+            ([28, 26, 26], 0.5),   // black: sand
+            ([54, 50, 50], 0.65),  // dark grey: basalt
+            ([104, 98, 94], 0.85), // grey: ash
+            ([196, 60, 20], 0.95), // red: lava
+            ([255, 186, 40], 1.0), // orange: crater
+        ])
+    }
+
+    /// Violet seas and teal plants.
+    pub fn alien() -> Self {
+        opaque(&[
+            ([36, 8, 54], 0.0),      // very dark violet: deep sea
+            ([92, 28, 120], 0.45),   // violet: sea
+            ([196, 72, 160], 0.5),   // magenta: shore
+            ([24, 150, 138], 0.52),  // teal: plants
+            ([150, 220, 60], 0.75),  // lime: plains
+            ([240, 236, 170], 0.92), // pale yellow: crystals
+            ([255, 255, 255], 1.0),  // white: summits
+        ])
+    }
+
+    /// Heights as shades of grey, from black to white.
+    pub fn grayscale() -> Self {
+        opaque(&[([0, 0, 0], 0.0), ([255, 255, 255], 1.0)])
+    }
+
+    /// Depths in shades of blue, under plain land.
+    pub fn bathymetric() -> Self {
+        opaque(&[
+            ([8, 29, 88], 0.0),
+            ([37, 52, 148], 0.1),
+            ([34, 94, 168], 0.2),
+            ([29, 145, 192], 0.3),
+            ([65, 182, 196], 0.4),
+            ([127, 205, 187], 0.45),
+            ([199, 233, 180], 0.5),
+            ([208, 200, 176], 0.5001), // land
+            ([208, 200, 176], 1.0),
+        ])
+    }
+
+    /// Conventional relief tints of topographic maps: blues below the sea,
+    /// greens, yellows and browns above, white on the highest summits.
+    pub fn hypsometric() -> Self {
+        opaque(&[
+            ([113, 171, 216], 0.0),
+            ([132, 185, 227], 0.2),
+            ([161, 210, 247], 0.4),
+            ([216, 242, 254], 0.5),
+            ([172, 208, 165], 0.5001),
+            ([148, 191, 139], 0.6),
+            ([168, 198, 143], 0.7),
+            ([189, 204, 150], 0.75),
+            ([209, 215, 171], 0.8),
+            ([239, 235, 192], 0.85),
+            ([222, 214, 163], 0.9),
+            ([195, 182, 157], 0.95),
+            ([245, 244, 242], 1.0),
+        ])
+    }
+
+    /// Cividis, which reads the same with the common color vision deficiencies.
+    pub fn colorblind_safe() -> Self {
+        opaque(&[
+            ([0, 34, 78], 0.0),
+            ([65, 77, 107], 0.25),
+            ([124, 123, 120], 0.5),
+            ([188, 175, 111], 0.75),
+            ([254, 232, 56], 1.0),
+        ])
+    }
+}
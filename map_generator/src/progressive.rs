@@ -0,0 +1,59 @@
+use super::{Generator, GeneratorType};
+use bon::bon;
+
+/// Part of a progressively generated map: RGBA pixels of whole rows, starting
+/// at row `start`.
+#[derive(Debug, Clone)]
+pub struct Rows {
+    pub start: usize,
+    pub data: Vec<u8>,
+}
+
+#[bon]
+impl Generator {
+    /// Generates the map in parts, to show it before it is complete. The first
+    /// part is a preview of the whole map, generated `preview` times smaller
+    /// and scaled back up, unless the map is cached or `preview` is 1. Then
+    /// come parts of `rows` rows, which put together make up `generate`.
+    #[builder]
+    pub fn generate_progressive(
+        &self,
+        generator_type: GeneratorType,
+        #[builder(default = 4)] preview: usize,
+        #[builder(default = 32)] rows: usize,
+    ) -> impl Iterator<Item = Rows> + '_ {
+        let preview = (preview > 1 && !self.is_cached(generator_type)).then_some(preview);
+
+        preview
+            .into_iter()
+            .map(move |scale| Rows {
+                start: 0,
+                data: self.preview(generator_type, scale),
+            })
+            .chain(
+                (0..self.height)
+                    .step_by(rows.max(1))
+                    .map(move |start| Rows {
+                        start,
+                        data: self
+                            .generate_rows(generator_type, start..(start + rows).min(self.height)),
+                    }),
+            )
+    }
+
+    fn preview(&self, generator_type: GeneratorType, scale: usize) -> Vec<u8> {
+        let preview = self.downscaled(scale);
+        let data = preview.generate(generator_type);
+        let data = &data;
+
+        (0..self.height)
+            .flat_map(|y| {
+                let row = y * preview.height / self.height * preview.width;
+                (0..self.width).flat_map(move |x| {
+                    let index = (row + x * preview.width / self.width) * 4;
+                    data[index..index + 4].iter().copied()
+                })
+            })
+            .collect()
+    }
+}
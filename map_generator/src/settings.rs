@@ -0,0 +1,471 @@
+use super::{
+    defaults::*, CellReturn, Color, ColorRamp, Distance, FractalMode, Generator, HeightmapKind,
+    Interpolation, IslandCurve, IslandMask, Neighbourhood, NoiseKind, RangeMode, Tiling, Vec3,
+    WarpMode,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Version of the settings documents written by this library. Documents of
+/// later versions are refused.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Sizes of the maps of valid settings, in pixels.
+pub const MIN_SIZE: usize = 1;
+pub const MAX_SIZE: usize = 8192;
+
+/// Largest loop counts of valid settings, which bound the generation time.
+pub const MAX_OCTAVE: u64 = 32;
+pub const MAX_EROSION_ITERATIONS: u64 = 1_000_000;
+pub const MAX_THERMAL_ITERATIONS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumIter)]
+pub enum SettingsFormat {
+    #[default]
+    #[strum(to_string = "JSON")]
+    Json,
+    #[strum(to_string = "TOML")]
+    Toml,
+}
+
+impl SettingsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SettingsFormat::Json => "json",
+            SettingsFormat::Toml => "toml",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            SettingsFormat::Json => "application/json",
+            SettingsFormat::Toml => "application/toml",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "json" => Some(SettingsFormat::Json),
+            "toml" => Some(SettingsFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Json(serde_json::Error),
+    TomlDecode(toml::de::Error),
+    TomlEncode(toml::ser::Error),
+    Version(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Json(e) => write!(f, "invalid JSON settings: {e}"),
+            SettingsError::TomlDecode(e) => write!(f, "invalid TOML settings: {e}"),
+            SettingsError::TomlEncode(e) => write!(f, "failed to write TOML settings: {e}"),
+            SettingsError::Version(version) => write!(
+                f,
+                "settings version {version} is newer than the supported version {SETTINGS_VERSION}"
+            ),
+            SettingsError::Invalid(e) => write!(f, "invalid settings: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<serde_json::Error> for SettingsError {
+    fn from(e: serde_json::Error) -> Self {
+        SettingsError::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for SettingsError {
+    fn from(e: toml::de::Error) -> Self {
+        SettingsError::TomlDecode(e)
+    }
+}
+
+impl From<toml::ser::Error> for SettingsError {
+    fn from(e: toml::ser::Error) -> Self {
+        SettingsError::TomlEncode(e)
+    }
+}
+
+/// Seeds are written as signed integers, which TOML is limited to, and read
+/// from either signed or unsigned ones.
+mod seed {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(*seed as i64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Seed {
+            Signed(i64),
+            Unsigned(u64),
+        }
+
+        Ok(match Seed::deserialize(deserializer)? {
+            Seed::Signed(seed) => seed as u64,
+            Seed::Unsigned(seed) => seed,
+        })
+    }
+}
+
+/// Every setting of a `Generator`, without its caches nor the imported image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorSettings {
+    #[serde(with = "seed")]
+    pub seed: u64,
+    pub interpolation: Interpolation,
+    pub noise: NoiseKind,
+    pub distance: Distance,
+    pub cell_return: CellReturn,
+    pub width: usize,
+    pub height: usize,
+    pub heightmap: HeightmapKind,
+    pub tiling: Tiling,
+    pub frequency: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub rotation: f64,
+    pub octave: u64,
+    pub lacunarity: f64,
+    pub persistence: f64,
+    pub fractal_mode: FractalMode,
+    pub warp_mode: WarpMode,
+    pub warp_strength: f64,
+    pub warp_frequency: f64,
+    pub range_mode: RangeMode,
+    pub erosion_iterations: u64,
+    pub erosion_inertia: f64,
+    pub sediment_capacity: f64,
+    pub deposition_rate: f64,
+    pub erosion_rate: f64,
+    pub evaporation: f64,
+    pub thermal_iterations: u64,
+    pub talus_angle: f64,
+    pub neighbourhood: Neighbourhood,
+    pub color_ramp: ColorRamp,
+    pub island_mask: IslandMask,
+    pub island_curve: IslandCurve,
+    pub island_center_x: f64,
+    pub island_center_y: f64,
+    pub island_radius: f64,
+    pub island_strength: f64,
+    pub flatten: bool,
+    pub use_shading: bool,
+    pub light_color: Color,
+    pub dark_color: Color,
+    pub light_position: Vec3,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        GeneratorSettings {
+            seed: DEFAULT_SEED,
+            interpolation: DEFAULT_INTERPOLATION,
+            noise: DEFAULT_NOISE,
+            distance: DEFAULT_DISTANCE,
+            cell_return: DEFAULT_CELL_RETURN,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            heightmap: DEFAULT_HEIGHTMAP,
+            tiling: DEFAULT_TILING,
+            frequency: DEFAULT_FREQUENCY,
+            offset_x: DEFAULT_OFFSET_X,
+            offset_y: DEFAULT_OFFSET_Y,
+            rotation: DEFAULT_ROTATION,
+            octave: DEFAULT_OCTAVE,
+            lacunarity: DEFAULT_LACUNARITY,
+            persistence: DEFAULT_PERSISTENCE,
+            fractal_mode: DEFAULT_FRACTAL_MODE,
+            warp_mode: DEFAULT_WARP_MODE,
+            warp_strength: DEFAULT_WARP_STRENGTH,
+            warp_frequency: DEFAULT_WARP_FREQUENCY,
+            range_mode: DEFAULT_RANGE_MODE,
+            erosion_iterations: DEFAULT_EROSION_ITERATIONS,
+            erosion_inertia: DEFAULT_EROSION_INERTIA,
+            sediment_capacity: DEFAULT_SEDIMENT_CAPACITY,
+            deposition_rate: DEFAULT_DEPOSITION_RATE,
+            erosion_rate: DEFAULT_EROSION_RATE,
+            evaporation: DEFAULT_EVAPORATION,
+            thermal_iterations: DEFAULT_THERMAL_ITERATIONS,
+            talus_angle: DEFAULT_TALUS_ANGLE,
+            neighbourhood: DEFAULT_NEIGHBOURHOOD,
+            color_ramp: ColorRamp::from(DEFAULT_COLOR_RAMP.to_vec()),
+            island_mask: DEFAULT_ISLAND_MASK,
+            island_curve: IslandCurve::from(DEFAULT_ISLAND_CURVE.to_vec()),
+            island_center_x: DEFAULT_ISLAND_CENTER_X,
+            island_center_y: DEFAULT_ISLAND_CENTER_Y,
+            island_radius: DEFAULT_ISLAND_RADIUS,
+            island_strength: DEFAULT_ISLAND_STRENGTH,
+            flatten: DEFAULT_FLATTEN,
+            use_shading: DEFAULT_USE_SHADING,
+            light_color: DEFAULT_LIGHT,
+            dark_color: DEFAULT_DARK,
+            light_position: DEFAULT_LIGHT_POSITION,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document<S> {
+    version: u32,
+    #[serde(flatten)]
+    settings: S,
+}
+
+impl GeneratorSettings {
+    /// Writes the settings as a document of the current version.
+    pub fn encode(&self, format: SettingsFormat) -> Result<String, SettingsError> {
+        let document = Document {
+            version: SETTINGS_VERSION,
+            settings: self,
+        };
+
+        Ok(match format {
+            SettingsFormat::Json => serde_json::to_string_pretty(&document)?,
+            SettingsFormat::Toml => toml::to_string(&document)?,
+        })
+    }
+
+    pub fn decode(text: &str, format: SettingsFormat) -> Result<Self, SettingsError> {
+        // The version is checked first, later versions may not parse at all.
+        let version = match format {
+            SettingsFormat::Json => serde_json::from_str::<Version>(text)?.version,
+            SettingsFormat::Toml => toml::from_str::<Version>(text)?.version,
+        };
+        if version > SETTINGS_VERSION {
+            return Err(SettingsError::Version(version));
+        }
+
+        let document: Document<Self> = match format {
+            SettingsFormat::Json => serde_json::from_str(text)?,
+            SettingsFormat::Toml => toml::from_str(text)?,
+        };
+        document.settings.validate()?;
+        Ok(document.settings)
+    }
+
+    /// Checks what the types do not: that the map size is within `MIN_SIZE`
+    /// and `MAX_SIZE`, that the loop counts are below their maximum, that the
+    /// color ramp has a step, that every number is finite and that the
+    /// frequency is positive.
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        for (name, size) in [("width", self.width), ("height", self.height)] {
+            if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+                return Err(SettingsError::Invalid(format!(
+                    "{name} must be between {MIN_SIZE} and {MAX_SIZE}"
+                )));
+            }
+        }
+
+        for (name, count, min, max) in [
+            ("octave", self.octave, 1, MAX_OCTAVE),
+            (
+                "erosion_iterations",
+                self.erosion_iterations,
+                0,
+                MAX_EROSION_ITERATIONS,
+            ),
+            (
+                "thermal_iterations",
+                self.thermal_iterations,
+                0,
+                MAX_THERMAL_ITERATIONS,
+            ),
+        ] {
+            if !(min..=max).contains(&count) {
+                return Err(SettingsError::Invalid(format!(
+                    "{name} must be between {min} and {max}"
+                )));
+            }
+        }
+
+        if self.color_ramp.steps().is_empty() {
+            return Err(SettingsError::Invalid(
+                "the color ramp has no step".to_string(),
+            ));
+        }
+
+        let numbers = [
+            ("frequency", self.frequency),
+            ("offset_x", self.offset_x),
+            ("offset_y", self.offset_y),
+            ("rotation", self.rotation),
+            ("lacunarity", self.lacunarity),
+            ("persistence", self.persistence),
+            ("warp_strength", self.warp_strength),
+            ("warp_frequency", self.warp_frequency),
+            ("erosion_inertia", self.erosion_inertia),
+            ("sediment_capacity", self.sediment_capacity),
+            ("deposition_rate", self.deposition_rate),
+            ("erosion_rate", self.erosion_rate),
+            ("evaporation", self.evaporation),
+            ("talus_angle", self.talus_angle),
+            ("island_center_x", self.island_center_x),
+            ("island_center_y", self.island_center_y),
+            ("island_radius", self.island_radius),
+            ("island_strength", self.island_strength),
+        ]
+        .into_iter()
+        .chain(
+            self.color_ramp
+                .steps()
+                .iter()
+                .map(|step| ("color_ramp", step.position)),
+        )
+        .chain(
+            Vec::from(self.island_curve.clone())
+                .into_iter()
+                .flat_map(|(x, y)| [("island_curve", x), ("island_curve", y)]),
+        )
+        .chain(self.light_position.0.map(|c| ("light_position", c)));
+
+        for (name, value) in numbers {
+            if !value.is_finite() {
+                return Err(SettingsError::Invalid(format!("{name} must be finite")));
+            }
+        }
+
+        if self.frequency <= 0.0 {
+            return Err(SettingsError::Invalid(
+                "frequency must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl From<GeneratorSettings> for Generator {
+    fn from(settings: GeneratorSettings) -> Self {
+        Generator::builder()
+            .seed(settings.seed)
+            .interpolation(settings.interpolation)
+            .noise(settings.noise)
+            .distance(settings.distance)
+            .cell_return(settings.cell_return)
+            .width(settings.width)
+            .height(settings.height)
+            .heightmap(settings.heightmap)
+            .tiling(settings.tiling)
+            .frequency(settings.frequency)
+            .offset_x(settings.offset_x)
+            .offset_y(settings.offset_y)
+            .rotation(settings.rotation)
+            .octave(settings.octave)
+            .lacunarity(settings.lacunarity)
+            .persistence(settings.persistence)
+            .fractal_mode(settings.fractal_mode)
+            .warp_mode(settings.warp_mode)
+            .warp_strength(settings.warp_strength)
+            .warp_frequency(settings.warp_frequency)
+            .range_mode(settings.range_mode)
+            .erosion_iterations(settings.erosion_iterations)
+            .erosion_inertia(settings.erosion_inertia)
+            .sediment_capacity(settings.sediment_capacity)
+            .deposition_rate(settings.deposition_rate)
+            .erosion_rate(settings.erosion_rate)
+            .evaporation(settings.evaporation)
+            .thermal_iterations(settings.thermal_iterations)
+            .talus_angle(settings.talus_angle)
+            .neighbourhood(settings.neighbourhood)
+            .color_ramp(settings.color_ramp)
+            .island_mask(settings.island_mask)
+            .island_curve(settings.island_curve)
+            .island_center_x(settings.island_center_x)
+            .island_center_y(settings.island_center_y)
+            .island_radius(settings.island_radius)
+            .island_strength(settings.island_strength)
+            .flatten(settings.flatten)
+            .use_shading(settings.use_shading)
+            .light_color(settings.light_color)
+            .dark_color(settings.dark_color)
+            .light_position(settings.light_position)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(settings: GeneratorSettings, format: SettingsFormat) -> Result<(), SettingsError> {
+        GeneratorSettings::decode(&settings.encode(format).unwrap(), format).map(|_| ())
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        for format in [SettingsFormat::Json, SettingsFormat::Toml] {
+            assert!(decode(GeneratorSettings::default(), format).is_ok());
+
+            for settings in [
+                GeneratorSettings {
+                    width: 0,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    height: MAX_SIZE + 1,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    color_ramp: ColorRamp::from(Vec::new()),
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    octave: 0,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    octave: MAX_OCTAVE + 1,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    erosion_iterations: MAX_EROSION_ITERATIONS + 1,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    thermal_iterations: MAX_THERMAL_ITERATIONS + 1,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    frequency: 0.0,
+                    ..Default::default()
+                },
+                GeneratorSettings {
+                    frequency: -1.0,
+                    ..Default::default()
+                },
+            ] {
+                assert!(matches!(
+                    decode(settings, format),
+                    Err(SettingsError::Invalid(_))
+                ));
+            }
+        }
+
+        // JSON has no infinite numbers, TOML does.
+        let settings = GeneratorSettings {
+            frequency: f64::INFINITY,
+            ..Default::default()
+        };
+        assert!(matches!(
+            decode(settings, SettingsFormat::Toml),
+            Err(SettingsError::Invalid(_))
+        ));
+    }
+}
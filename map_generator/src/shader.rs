@@ -0,0 +1,65 @@
+use super::{color::lerp, Color, Tiling, Vec3};
+
+/// Difference between the neighbours of `c` along an axis of `size` values
+/// read by `at`, which wrap around when `wraps`. Single values are flat.
+fn difference(at: impl Fn(usize) -> f64, c: usize, size: usize, wraps: bool) -> f64 {
+    if size < 2 {
+        return 0.0;
+    }
+
+    let last = size - 1;
+    if c == 0 && wraps {
+        at(1) - at(last)
+    } else if c == last && wraps {
+        at(0) - at(last - 1)
+    } else if c == 0 {
+        (at(1) - at(0)) * 2.0
+    } else if c == last {
+        (at(last) - at(last - 1)) * 2.0
+    } else {
+        at(c + 1) - at(c - 1)
+    }
+}
+
+/// Surface normal from central differences, which wrap around the tiled axes.
+pub fn normal(heightmap: &[f64], index: usize, width: usize, scale: f64, tiling: Tiling) -> Vec3 {
+    let x = index % width;
+    let y = index / width;
+    let height = heightmap.len() / width;
+
+    let nx = difference(|x| heightmap[x + y * width], x, width, tiling.horizontal()) * scale;
+    let ny = difference(|y| heightmap[x + y * width], y, height, tiling.vertical()) * scale;
+
+    let n = (nx * nx + ny * ny + 4.0).sqrt();
+    Vec3([-nx / n, -ny / n, 2.0 / n])
+}
+
+#[bon::builder]
+pub fn shade(
+    heightmap: &[f64],
+    index: usize,
+    color: Color,
+    width: usize,
+    tiling: Tiling,
+    light: &Vec3,
+    light_color: Color,
+    dark_color: Color,
+) -> Color {
+    if heightmap[index] > 0.5 {
+        let normal = normal(heightmap, index, width, 1.0, tiling);
+
+        let d = light.dot(&normal) * 25.0 + 0.5;
+
+        if d < 0.0 {
+            dark_color
+        } else if d > 1.0 {
+            light_color
+        } else if d < 0.5 {
+            lerp(dark_color, color, 2.0 * d)
+        } else {
+            lerp(color, light_color, 2.0 * d - 1.0)
+        }
+    } else {
+        color
+    }
+}
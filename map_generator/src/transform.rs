@@ -0,0 +1,40 @@
+use super::noise::Extent;
+
+/// Size of the area covered by a map: the unit square, stretched along the
+/// longest side to keep the aspect ratio.
+pub fn map_area(width: usize, height: usize) -> (f64, f64) {
+    let wf = width as f64;
+    let hf = height as f64;
+    ((wf / hf).max(1.0), (hf / wf).max(1.0))
+}
+
+/// Maps points of the map area to the noise domain: a rotation about `center`,
+/// then an offset in map units, then a scale by `frequency`.
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub frequency: f64,
+    pub offset: (f64, f64),
+    /// Rotation in degrees.
+    pub rotation: f64,
+    pub center: (f64, f64),
+}
+
+impl Transform {
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let (dx, dy) = (x - self.center.0, y - self.center.1);
+
+        (
+            (self.center.0 + self.offset.0 + dx * cos - dy * sin) * self.frequency,
+            (self.center.1 + self.offset.1 + dx * sin + dy * cos) * self.frequency,
+        )
+    }
+
+    /// Size in the noise domain of an `extent` of the map area.
+    pub fn extent(&self, extent: Extent) -> Extent {
+        (
+            extent.0.map(|e| e * self.frequency),
+            extent.1.map(|e| e * self.frequency),
+        )
+    }
+}
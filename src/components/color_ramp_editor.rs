@@ -0,0 +1,159 @@
+use dioxus::prelude::*;
+use map_generator::{Color, ColorRamp, ColorRampStep};
+use std::rc::Rc;
+
+/// Distance in pixels from a step within which the bar grabs it.
+const GRAB_DISTANCE: f64 = 6.0;
+
+#[derive(PartialEq, Debug, Clone, Props)]
+pub struct Props {
+    value: ColorRamp,
+    onchange: EventHandler<ColorRamp>,
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn parse_hex(hex: &str, alpha: u8) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color([channel(0)?, channel(2)?, channel(4)?, alpha]))
+}
+
+fn gradient(ramp: &ColorRamp) -> String {
+    let steps = ramp.steps();
+    // The ramp keeps the colors of its ends beyond them.
+    let first = steps.first().map(|step| (step.color, 0.0));
+    let last = steps.last().map(|step| (step.color, 1.0));
+    let stops: Vec<String> = first
+        .into_iter()
+        .chain(steps.iter().map(|step| (step.color, step.position)))
+        .chain(last)
+        .map(|(color, position)| {
+            format!(
+                "rgba({}, {}, {}, {}) {}%",
+                color[0],
+                color[1],
+                color[2],
+                color[3] as f64 / 255.0,
+                position * 100.0
+            )
+        })
+        .collect();
+    format!("linear-gradient(to right, {})", stops.join(", "))
+}
+
+/// Gradient bar of a color ramp with a marker for each step. Clicking a marker
+/// selects it and drags it along the bar, double-clicking the bar adds a step,
+/// and the selected step can be recolored or deleted.
+#[component]
+pub fn element(props: Props) -> Element {
+    // Steps in the order they were added, so that a dragged step keeps its index.
+    let mut steps = use_signal(|| props.value.steps().to_vec());
+    let mut selected = use_signal(|| None::<usize>);
+    let mut dragging = use_signal(|| false);
+    let mut bar = use_signal(|| None::<Rc<MountedData>>);
+    let mut bar_width = use_signal(|| 1.0);
+
+    // Follow ramps changed from outside the editor, but not the ones it sent.
+    use_effect(use_reactive((&props.value,), move |(value,)| {
+        if value != ColorRamp::from(steps.peek().clone()) {
+            steps.set(value.steps().to_vec());
+            selected.set(None);
+        }
+    }));
+
+    let mut change = move |new_steps: Vec<ColorRampStep>| {
+        props.onchange.call(ColorRamp::from(new_steps.clone()));
+        steps.set(new_steps);
+    };
+
+    let ramp = ColorRamp::from(steps.read().clone());
+    let selected_step = selected.read().and_then(|i| steps.read().get(i).cloned());
+
+    rsx! {
+        div {
+            class: "color-ramp",
+            style: "background: {gradient(&ramp)}",
+            onmounted: move |e| bar.set(Some(e.data())),
+            onmousedown: move |e| async move {
+                if let Some(bar) = bar.read().clone() {
+                    if let Ok(rect) = bar.get_client_rect().await {
+                        bar_width.set(rect.size.width.max(1.0));
+                    }
+                }
+                let x = e.element_coordinates().x;
+                let width = *bar_width.read();
+                let nearest = steps
+                    .read()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, step)| (i, (step.position * width - x).abs()))
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .filter(|(_, distance)| *distance <= GRAB_DISTANCE)
+                    .map(|(i, _)| i);
+                selected.set(nearest);
+                dragging.set(nearest.is_some());
+            },
+            onmousemove: move |e| {
+                if let (true, Some(i)) = (*dragging.read(), *selected.read()) {
+                    let position = (e.element_coordinates().x / *bar_width.read()).clamp(0.0, 1.0);
+                    let mut new_steps = steps.read().clone();
+                    new_steps[i].position = position;
+                    change(new_steps);
+                }
+            },
+            onmouseup: move |_| dragging.set(false),
+            onmouseleave: move |_| dragging.set(false),
+            ondoubleclick: move |e| {
+                // The clicks before grabbed a step instead.
+                if selected.read().is_some() {
+                    return;
+                }
+                let position = (e.element_coordinates().x / *bar_width.read()).clamp(0.0, 1.0);
+                let color = ColorRamp::from(steps.read().clone()).get(position);
+                let mut new_steps = steps.read().clone();
+                new_steps.push(ColorRampStep { color, position });
+                selected.set(Some(new_steps.len() - 1));
+                change(new_steps);
+            },
+            {
+                steps.read().iter().enumerate().map(|(i, step)| rsx! {
+                    div {
+                        class: if *selected.read() == Some(i) { "color-ramp-step selected" } else { "color-ramp-step" },
+                        style: "left: {step.position * 100.0}%; background-color: {hex(step.color)}",
+                    }
+                })
+            }
+        }
+        if let (Some(i), Some(step)) = (*selected.read(), selected_step) {
+            div {
+                class: "color-ramp-selection",
+                input {
+                    r#type: "color",
+                    value: hex(step.color),
+                    oninput: move |e| {
+                        if let Some(color) = parse_hex(&e.value(), step.color[3]) {
+                            let mut new_steps = steps.read().clone();
+                            new_steps[i].color = color;
+                            change(new_steps);
+                        }
+                    }
+                }
+                span { "{(step.position * 100.0).round()}%" }
+                button {
+                    // The ramp needs a step to take its colors from.
+                    disabled: steps.read().len() <= 1,
+                    onclick: move |_| {
+                        let mut new_steps = steps.read().clone();
+                        new_steps.remove(i);
+                        selected.set(None);
+                        change(new_steps);
+                    },
+                    "Delete"
+                }
+            }
+        }
+    }
+}
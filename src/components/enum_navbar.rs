@@ -1,3 +1,4 @@
+use super::use_synced_signal;
 use dioxus::prelude::*;
 use std::fmt::Debug;
 use strum::IntoEnumIterator;
@@ -14,7 +15,7 @@ pub fn element<
 >(
     props: Props<T>,
 ) -> Element {
-    let mut current = use_signal(|| props.value.unwrap_or_default());
+    let mut current = use_synced_signal(props.value, T::default);
 
     rsx! {
         div {
@@ -1,3 +1,4 @@
+use super::use_synced_signal;
 use dioxus::prelude::*;
 use std::fmt::Debug;
 use strum::IntoEnumIterator;
@@ -15,7 +16,7 @@ pub fn element<
 >(
     props: Props<T>,
 ) -> Element {
-    let mut value = use_signal(|| props.value.unwrap_or_default());
+    let mut value = use_synced_signal(props.value, T::default);
 
     rsx! {
         select {
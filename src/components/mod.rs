@@ -1,9 +1,13 @@
+mod color_ramp_editor;
 mod enum_navbar;
 mod enum_select;
 mod random_number_input;
 mod slidebar;
+mod synced_signal;
 
+pub use color_ramp_editor::element as ColorRampEditor;
 pub use enum_navbar::element as EnumNavbar;
 pub use enum_select::element as EnumSelect;
 pub use random_number_input::element as RandomNumberInput;
 pub use slidebar::element as Slidebar;
+pub use synced_signal::use_synced_signal;
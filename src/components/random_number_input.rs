@@ -1,3 +1,4 @@
+use super::use_synced_signal;
 use dioxus::prelude::*;
 use rand::{thread_rng, Rng};
 
@@ -9,7 +10,7 @@ pub struct Props {
 
 #[component]
 pub fn element(props: Props) -> Element {
-    let mut value = use_signal(|| props.value.unwrap_or(0));
+    let mut value = use_synced_signal(props.value, || 0);
 
     rsx! {
         input {
@@ -1,3 +1,4 @@
+use super::use_synced_signal;
 use dioxus::prelude::*;
 use std::str::FromStr;
 
@@ -17,7 +18,7 @@ pub fn element<
 >(
     props: Props<T>,
 ) -> Element {
-    let mut value = use_signal(|| props.value.unwrap_or(props.min));
+    let mut value = use_synced_signal(props.value, || props.min);
 
     rsx! {
         input {
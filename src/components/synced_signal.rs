@@ -0,0 +1,18 @@
+use dioxus::prelude::*;
+
+/// Signal of a component starting from `value`, or `default` without one,
+/// which follows the values later passed from outside the component.
+pub fn use_synced_signal<T: 'static + PartialEq + Clone>(
+    value: Option<T>,
+    default: impl FnOnce() -> T,
+) -> Signal<T> {
+    let mut signal = use_signal(|| value.clone().unwrap_or_else(default));
+
+    use_effect(use_reactive((&value,), move |(value,)| {
+        if let Some(value) = value {
+            signal.set(value);
+        }
+    }));
+
+    signal
+}
@@ -0,0 +1,50 @@
+use std::cell::Cell;
+use web_sys::wasm_bindgen::{closure::Closure, JsCast};
+
+/// Runs the latest function it is given once no other has been given for
+/// `delay` milliseconds.
+pub struct Debounce {
+    delay: i32,
+    handle: Cell<Option<i32>>,
+    /// Kept until the next call, the timeout may not have run it yet.
+    callback: Cell<Option<Closure<dyn FnMut()>>>,
+}
+
+impl Debounce {
+    pub fn new(delay: i32) -> Self {
+        Self {
+            delay,
+            handle: Cell::new(None),
+            callback: Cell::new(None),
+        }
+    }
+
+    pub fn call(&self, f: impl FnOnce() + 'static) {
+        let window = web_sys::window().expect("Failed to get window");
+        self.cancel();
+
+        let callback = Closure::once(f);
+        let handle = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.as_ref().unchecked_ref(),
+                self.delay,
+            )
+            .expect("Failed to set timeout");
+        self.handle.set(Some(handle));
+        self.callback.set(Some(callback));
+    }
+
+    fn cancel(&self) {
+        if let Some(handle) = self.handle.take() {
+            web_sys::window()
+                .expect("Failed to get window")
+                .clear_timeout_with_handle(handle);
+        }
+    }
+}
+
+impl Drop for Debounce {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
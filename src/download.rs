@@ -0,0 +1,26 @@
+use web_sys::{js_sys, wasm_bindgen::JsCast};
+
+pub fn download(filename: &str, mime: &str, data: &[u8]) {
+    let document = web_sys::window()
+        .expect("Failed to get window")
+        .document()
+        .expect("Failed to get document");
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(data));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .expect("Failed to create blob");
+    let url = web_sys::Url::create_object_url_with_blob(&blob).expect("Failed to create url");
+
+    let anchor = document
+        .create_element("a")
+        .expect("Failed to create anchor")
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .expect("Failed to convert to HtmlAnchorElement");
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).expect("Failed to revoke url");
+}
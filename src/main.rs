@@ -1,75 +1,103 @@
 #![allow(non_snake_case)]
 mod components;
-mod defaults;
-mod map_generator;
+mod debounce;
+mod download;
+mod permalink;
 mod settings;
+mod storage;
+mod worker;
 
-use defaults::*;
 use dioxus::prelude::*;
 use dioxus_logger::tracing::{info, Level};
-use map_generator::{ColorRamp, Generator};
-use web_sys::wasm_bindgen::JsCast;
+use map_generator::{
+    defaults::*, Generator, GeneratorSettings, GeneratorType, HeightmapFormat, HeightmapKind,
+    MeshColors, MeshFormat, SettingsFormat,
+};
+use std::rc::Rc;
+use web_sys::wasm_bindgen::{closure::Closure, JsCast};
+use worker::Export;
+
+/// Milliseconds without changes after which the settings are saved.
+const SAVE_DELAY: i32 = 300;
 
 fn main() {
     dioxus_logger::init(Level::INFO).expect("Failed to initialize logger");
+
+    // The generation worker loads this module too, without a page to render.
+    if web_sys::window().is_none() {
+        worker::set_panic_hook();
+        return;
+    }
+
     info!("Starting the application");
     launch(App);
 }
 
 #[component]
 fn App() -> Element {
-    let mut width = use_signal(|| 512);
-    let mut height = use_signal(|| 512);
-
-    let mut generator_type = use_signal(|| DEFAULT_GENERATOR_TYPE);
-    let mut generator = use_signal(|| {
-        Generator::builder()
-            .seed(DEFAULT_SEED)
-            .interpolation(DEFAULT_INTERPOLATION)
-            .noise(DEFAULT_NOISE)
-            .width(DEFAULT_WIDTH)
-            .height(DEFAULT_HEIGHT)
-            .heightmap(DEFAULT_HEIGHTMAP)
-            .octave(DEFAULT_OCTAVE)
-            .lacunarity(DEFAULT_LACUNARITY)
-            .persistence(DEFAULT_PERSISTENCE)
-            .color_ramp(ColorRamp::from(DEFAULT_COLOR_RAMP.to_vec()))
-            .light_color(DEFAULT_LIGHT)
-            .dark_color(DEFAULT_DARK)
-            .light_position(DEFAULT_LIGHT_POSITION)
-            .flatten(DEFAULT_FLATTEN)
-            .use_shading(DEFAULT_USE_SHADING)
-            .build()
+    // The map of a permalink, or of the last session, or the default one.
+    let (initial_type, initial_settings) = use_hook(|| {
+        permalink::read()
+            .or_else(storage::read)
+            .unwrap_or((DEFAULT_GENERATOR_TYPE, GeneratorSettings::default()))
+    });
+
+    let mut width = use_signal(|| initial_settings.width as i64);
+    let mut height = use_signal(|| initial_settings.height as i64);
+
+    let mut generator_type = use_signal(|| initial_type);
+    let mut heightmap = use_signal(|| initial_settings.heightmap);
+    let mut cursor = use_signal(|| (0.0, 0.0));
+    let mut dragging = use_signal(|| false);
+    let mut generator = use_signal(|| Generator::from(initial_settings));
+
+    let mut apply_settings = move |settings: GeneratorSettings| {
+        *width.write() = settings.width as i64;
+        *height.write() = settings.height as i64;
+        *heightmap.write() = settings.heightmap;
+        generator.write().set_settings(settings);
+    };
+
+    // Links pasted in the address bar only change the fragment of the page.
+    let onhashchange = use_hook(|| {
+        let onhashchange = Rc::new(Closure::<dyn FnMut()>::new(move || {
+            if let Some((type_, settings)) = permalink::read() {
+                *generator_type.write() = type_;
+                apply_settings(settings);
+            }
+        }));
+        web_sys::window()
+            .expect("Failed to get window")
+            .set_onhashchange(Some((*onhashchange).as_ref().unchecked_ref()));
+        onhashchange
+    });
+    use_drop(move || {
+        web_sys::window()
+            .expect("Failed to get window")
+            .set_onhashchange(None);
+        drop(onhashchange);
+    });
+
+    let worker = use_hook(|| Rc::new(worker::GenerationWorker::new(draw)));
+
+    use_effect({
+        let worker = worker.clone();
+        move || worker.generate(*generator_type.read(), generator.read().settings())
+    });
+
+    // Sliders change the settings on every step of a drag, and browsers limit
+    // how often the URL can be replaced.
+    let save = use_hook(|| Rc::new(debounce::Debounce::new(SAVE_DELAY)));
+    use_effect(move || {
+        let generator_type = *generator_type.read();
+        let settings = generator.read().settings();
+        save.call(move || {
+            permalink::write(generator_type, &settings);
+            storage::write(generator_type, &settings);
+        });
     });
 
-    use_effect(use_reactive((), move |()| {
-        let window = web_sys::window().expect("Failed to get window");
-        let canvas = window
-            .document()
-            .expect("Failed to get document")
-            .get_element_by_id("canvas")
-            .expect("Failed to get canvas")
-            .dyn_into::<web_sys::HtmlCanvasElement>()
-            .expect("Failed to convert to HtmlCanvasElement");
-        let context = canvas
-            .get_context("2d")
-            .expect("Failed to get 2d context")
-            .expect("Failed to get 2d context (2nd time)")
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .expect("Failed to convert to CanvasRenderingContext2d");
-
-        let data = generator.read().generate(*generator_type.read());
-
-        let data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
-            web_sys::wasm_bindgen::Clamped(&data),
-            *width.read() as u32,
-            *height.read() as u32,
-        )
-        .unwrap();
-        context
-            .put_image_data(&data, 0.0, 0.0)
-            .expect("Failed to put image data");
-    }));
+    let settings = generator.read().settings();
 
     rsx! {
         link { rel: "stylesheet", href: "main.css" }
@@ -77,6 +105,17 @@ fn App() -> Element {
             onseedchange: move |seed| generator.write().set_seed(seed as u64),
             oninterpolationchange: move |interpolation| generator.write().set_interpolation(interpolation),
             onnoisechange: move |noise| generator.write().set_noise(noise),
+            ondistancechange: move |distance| generator.write().set_distance(distance),
+            oncellreturnchange: move |cell_return| generator.write().set_cell_return(cell_return),
+            onrangemodechange: move |range_mode| generator.write().set_range_mode(range_mode),
+            onwarpmodechange: move |mode| generator.write().set_warp_mode(mode),
+            onwarpstrengthchange: move |strength| generator.write().set_warp_strength(strength),
+            onwarpfrequencychange: move |frequency| generator.write().set_warp_frequency(frequency),
+            onfrequencychange: move |frequency| generator.write().set_frequency(frequency),
+            onoffsetxchange: move |offset| generator.write().set_offset_x(offset),
+            onoffsetychange: move |offset| generator.write().set_offset_y(offset),
+            onrotationchange: move |rotation| generator.write().set_rotation(rotation),
+            ontilingchange: move |tiling| generator.write().set_tiling(tiling),
             onwidthchange: move |w| {
                 generator.write().set_width(w as usize);
                 *width.write() = w;
@@ -85,24 +124,108 @@ fn App() -> Element {
                 generator.write().set_height(h as usize);
                 *height.write() = h;
             },
-            onheightmapchange: move |heightmap| generator.write().set_heightmap(heightmap),
+            onheightmapchange: move |kind| {
+                generator.write().set_heightmap(kind);
+                *heightmap.write() = kind;
+            },
+            onimagechange: {
+                let worker = worker.clone();
+                move |(image, bytes)| {
+                    worker.set_image(bytes);
+                    generator.write().set_image(Some(image));
+                }
+            },
             onoctavechange: move |octave| generator.write().set_octave(octave as u64),
             onpersistencechange: move |persistence| generator.write().set_persistence(persistence),
             onlacunaritychange: move |lacunarity| generator.write().set_lacunarity(lacunarity),
+            onfractalmodechange: move |mode| generator.write().set_fractal_mode(mode),
+            onerosioniterationschange: move |iterations| generator.write().set_erosion_iterations(iterations as u64),
+            onerosioninertiachange: move |inertia| generator.write().set_erosion_inertia(inertia),
+            onsedimentcapacitychange: move |capacity| generator.write().set_sediment_capacity(capacity),
+            ondepositionratechange: move |rate| generator.write().set_deposition_rate(rate),
+            onerosionratechange: move |rate| generator.write().set_erosion_rate(rate),
+            onevaporationchange: move |evaporation| generator.write().set_evaporation(evaporation),
+            onthermaliterationschange: move |iterations| generator.write().set_thermal_iterations(iterations as u64),
+            ontalusanglechange: move |angle| generator.write().set_talus_angle(angle),
+            onneighbourhoodchange: move |neighbourhood| generator.write().set_neighbourhood(neighbourhood),
+            onislandmaskchange: move |island_mask| generator.write().set_island_mask(island_mask),
+            onislandcenterxchange: move |x| generator.write().set_island_center_x(x),
+            onislandcenterychange: move |y| generator.write().set_island_center_y(y),
+            onislandradiuschange: move |radius| generator.write().set_island_radius(radius),
+            onislandstrengthchange: move |strength| generator.write().set_island_strength(strength),
+            onislandcurvechange: move |curve| generator.write().set_island_curve(curve),
             onflattenchange: move |flatten| generator.write().set_flatten(flatten),
             onuseshadingchange: move |use_shading| generator.write().set_use_shading(use_shading),
+            oncolorrampchange: move |color_ramp| generator.write().set_color_ramp(color_ramp),
+            onimportsettings: move |settings: GeneratorSettings| apply_settings(settings),
+            onresetsettings: move |()| {
+                *generator_type.write() = DEFAULT_GENERATOR_TYPE;
+                apply_settings(GeneratorSettings::default());
+            },
+            onexportsettings: move |format: SettingsFormat| {
+                let settings = generator
+                    .read()
+                    .settings()
+                    .encode(format)
+                    .expect("Failed to encode settings");
+                download::download(&format!("Settings.{}", format.extension()), format.mime(), settings.as_bytes());
+            },
+            ondownload: {
+                let worker = worker.clone();
+                move |()| worker.export(generator.read().settings(), Export::Png(*generator_type.read()))
+            },
+            ondownloadheightmap: {
+                let worker = worker.clone();
+                move |(format, min, max): (HeightmapFormat, f64, f64)| {
+                    worker.export(generator.read().settings(), Export::Heightmap { format, min, max });
+                }
+            },
+            ondownloadmesh: {
+                let worker = worker.clone();
+                move |(format, colors, vertical_scale): (MeshFormat, MeshColors, f64)| {
+                    worker.export(generator.read().settings(), Export::Mesh { format, colors, vertical_scale });
+                }
+            },
             generator_type: *generator_type.read(),
-            seed: DEFAULT_SEED as i64,
-            interpolation: DEFAULT_INTERPOLATION,
-            noise: DEFAULT_NOISE,
-            width: DEFAULT_WIDTH as i64,
-            height: DEFAULT_HEIGHT as i64,
-            heightmap: DEFAULT_HEIGHTMAP,
-            octave: DEFAULT_OCTAVE as i64,
-            persistence: DEFAULT_PERSISTENCE,
-            lacunarity: DEFAULT_LACUNARITY,
-            flatten: DEFAULT_FLATTEN,
-            use_shading: DEFAULT_USE_SHADING,
+            seed: settings.seed as i64,
+            interpolation: settings.interpolation,
+            noise: settings.noise,
+            distance: settings.distance,
+            cell_return: settings.cell_return,
+            range_mode: settings.range_mode,
+            warp_mode: settings.warp_mode,
+            warp_strength: settings.warp_strength,
+            warp_frequency: settings.warp_frequency,
+            frequency: settings.frequency,
+            offset_x: settings.offset_x,
+            offset_y: settings.offset_y,
+            rotation: settings.rotation,
+            tiling: settings.tiling,
+            width: settings.width as i64,
+            height: settings.height as i64,
+            heightmap: settings.heightmap,
+            octave: settings.octave as i64,
+            persistence: settings.persistence,
+            lacunarity: settings.lacunarity,
+            fractal_mode: settings.fractal_mode,
+            erosion_iterations: settings.erosion_iterations as i64,
+            erosion_inertia: settings.erosion_inertia,
+            sediment_capacity: settings.sediment_capacity,
+            deposition_rate: settings.deposition_rate,
+            erosion_rate: settings.erosion_rate,
+            evaporation: settings.evaporation,
+            thermal_iterations: settings.thermal_iterations as i64,
+            talus_angle: settings.talus_angle,
+            neighbourhood: settings.neighbourhood,
+            island_mask: settings.island_mask,
+            island_center_x: settings.island_center_x,
+            island_center_y: settings.island_center_y,
+            island_radius: settings.island_radius,
+            island_strength: settings.island_strength,
+            island_curve: settings.island_curve,
+            flatten: settings.flatten,
+            use_shading: settings.use_shading,
+            color_ramp: settings.color_ramp,
         }
         div { class: "vertical-divider" }
         div {
@@ -117,9 +240,56 @@ fn App() -> Element {
                     id: "canvas",
                     width: "{width}",
                     height: "{height}",
+                    prevent_default: "onwheel",
+                    // Only the Noise view and the Fractal heightmap sample a region of the noise.
+                    onwheel: move |e| {
+                        if *generator_type.read() == GeneratorType::Noise
+                            || *heightmap.read() == HeightmapKind::Fractal
+                        {
+                            let factor = (e.delta().strip_units().y * 0.001).exp();
+                            let (x, y) = *cursor.read();
+                            generator.write().zoom(factor, x, y);
+                        }
+                    },
+                    onmousedown: move |_| *dragging.write() = true,
+                    onmousemove: move |e| {
+                        let point = e.element_coordinates();
+                        let (x, y) = *cursor.read();
+                        *cursor.write() = (point.x, point.y);
+
+                        if *dragging.read()
+                            && (*generator_type.read() == GeneratorType::Noise
+                                || *heightmap.read() == HeightmapKind::Fractal)
+                        {
+                            generator.write().pan(point.x - x, point.y - y);
+                        }
+                    },
+                    onmouseup: move |_| *dragging.write() = false,
+                    onmouseleave: move |_| *dragging.write() = false,
                 }
 
             }
         }
     }
 }
+
+fn draw(image: web_sys::ImageData, y: f64) {
+    let window = web_sys::window().expect("Failed to get window");
+    let canvas = window
+        .document()
+        .expect("Failed to get document")
+        .get_element_by_id("canvas")
+        .expect("Failed to get canvas")
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .expect("Failed to convert to HtmlCanvasElement");
+    let context = canvas
+        .get_context("2d")
+        .expect("Failed to get 2d context")
+        .expect("Failed to get 2d context (2nd time)")
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .expect("Failed to convert to CanvasRenderingContext2d");
+
+    context
+        .put_image_data(&image, 0.0, y)
+        .expect("Failed to put image data");
+}
@@ -1,15 +0,0 @@
-mod color;
-mod generator;
-mod heightmap;
-mod interpolation;
-mod noise;
-mod shader;
-mod vec3;
-
-pub use color::{Color, ColorRamp, ColorRampStep};
-pub use generator::{Generator, GeneratorType};
-pub use heightmap::HeightmapKind;
-pub use interpolation::Interpolation;
-pub use noise::NoiseKind;
-pub use shader::shade;
-pub use vec3::Vec3;
@@ -0,0 +1,108 @@
+//! Permalinks of the map shown: the view and every setting, as base64url
+//! encoded JSON in the fragment of the page URL. The saved settings use the
+//! same JSON.
+
+use crate::settings::fit_size;
+use dioxus_logger::tracing::error;
+use map_generator::{GeneratorSettings, GeneratorType, SettingsFormat, SETTINGS_VERSION};
+use serde::{Deserialize, Serialize};
+use web_sys::wasm_bindgen::JsValue;
+
+#[derive(Serialize)]
+struct Permalink<S> {
+    version: u32,
+    generator_type: GeneratorType,
+    #[serde(flatten)]
+    settings: S,
+}
+
+/// Fields of a permalink besides the settings document.
+#[derive(Deserialize)]
+struct View {
+    generator_type: GeneratorType,
+}
+
+/// Map of the page URL, if it has one.
+pub fn read() -> Option<(GeneratorType, GeneratorSettings)> {
+    let window = web_sys::window().expect("Failed to get window");
+    let hash = window
+        .location()
+        .hash()
+        .expect("Failed to get location hash");
+    let fragment = hash
+        .strip_prefix('#')
+        .filter(|fragment| !fragment.is_empty())?;
+
+    match decode(&window, fragment) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            error!("Failed to read permalink: {e}");
+            None
+        }
+    }
+}
+
+fn decode(
+    window: &web_sys::Window,
+    fragment: &str,
+) -> Result<(GeneratorType, GeneratorSettings), String> {
+    let base64: String = fragment
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    let json = window
+        .atob(&base64)
+        .map_err(|_| "invalid base64".to_string())?;
+    from_json(&json)
+}
+
+/// Reads a permalink or saved settings, with a map size within the bounds of
+/// the sliders.
+pub fn from_json(json: &str) -> Result<(GeneratorType, GeneratorSettings), String> {
+    let settings =
+        GeneratorSettings::decode(json, SettingsFormat::Json).map_err(|e| e.to_string())?;
+    let view: View = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    Ok((view.generator_type, fit_size(settings)))
+}
+
+pub fn to_json(generator_type: GeneratorType, settings: &GeneratorSettings) -> String {
+    serde_json::to_string(&Permalink {
+        version: SETTINGS_VERSION,
+        generator_type,
+        settings,
+    })
+    .expect("Failed to serialize permalink")
+}
+
+/// Replaces the URL of the page by the permalink of the map, without adding an
+/// entry to the history.
+pub fn write(generator_type: GeneratorType, settings: &GeneratorSettings) {
+    let window = web_sys::window().expect("Failed to get window");
+
+    let json = to_json(generator_type, settings);
+
+    // The JSON is ASCII only, which `btoa` requires.
+    let fragment: String = window
+        .btoa(&json)
+        .expect("Failed to encode permalink")
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect();
+
+    if let Err(e) = window
+        .history()
+        .expect("Failed to get history")
+        .replace_state_with_url(&JsValue::NULL, "", Some(&format!("#{fragment}")))
+    {
+        error!("Failed to update URL: {e:?}");
+    }
+}
@@ -1,8 +1,30 @@
 use crate::{
-    components::{EnumSelect, RandomNumberInput, Slidebar},
-    map_generator::{GeneratorType, HeightmapKind, Interpolation, NoiseKind},
+    components::{use_synced_signal, ColorRampEditor, EnumSelect, RandomNumberInput, Slidebar},
+    storage,
 };
 use dioxus::prelude::*;
+use dioxus_logger::tracing::error;
+use map_generator::{
+    CellReturn, ColorRamp, ColorRampPreset, Distance, FractalMode, GeneratorSettings,
+    GeneratorType, HeightmapFormat, HeightmapImage, HeightmapKind, Interpolation, IslandCurve,
+    IslandMask, MeshColors, MeshFormat, Neighbourhood, NoiseKind, RangeMode, SettingsFormat,
+    Tiling, WarpMode,
+};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+/// Bounds of the width and height sliders, which the maps of the app keep to.
+const MIN_SIZE: usize = 64;
+const MAX_SIZE: usize = 1024;
+
+/// Settings with a map size within the bounds of the sliders.
+pub fn fit_size(settings: GeneratorSettings) -> GeneratorSettings {
+    GeneratorSettings {
+        width: settings.width.clamp(MIN_SIZE, MAX_SIZE),
+        height: settings.height.clamp(MIN_SIZE, MAX_SIZE),
+        ..settings
+    }
+}
 
 #[derive(PartialEq, Debug, Clone, Props)]
 pub struct Props {
@@ -10,31 +32,129 @@ pub struct Props {
     seed: Option<i64>,
     interpolation: Option<Interpolation>,
     noise: Option<NoiseKind>,
+    distance: Option<Distance>,
+    cell_return: Option<CellReturn>,
+    warp_mode: Option<WarpMode>,
+    warp_strength: Option<f64>,
+    warp_frequency: Option<f64>,
+    frequency: Option<f64>,
+    offset_x: Option<f64>,
+    offset_y: Option<f64>,
+    rotation: Option<f64>,
+    tiling: Option<Tiling>,
     width: Option<i64>,
     height: Option<i64>,
     heightmap: Option<HeightmapKind>,
     octave: Option<i64>,
     persistence: Option<f64>,
     lacunarity: Option<f64>,
+    fractal_mode: Option<FractalMode>,
+    range_mode: Option<RangeMode>,
+    erosion_iterations: Option<i64>,
+    erosion_inertia: Option<f64>,
+    sediment_capacity: Option<f64>,
+    deposition_rate: Option<f64>,
+    erosion_rate: Option<f64>,
+    evaporation: Option<f64>,
+    thermal_iterations: Option<i64>,
+    talus_angle: Option<f64>,
+    neighbourhood: Option<Neighbourhood>,
+    island_mask: Option<IslandMask>,
+    island_center_x: Option<f64>,
+    island_center_y: Option<f64>,
+    island_radius: Option<f64>,
+    island_strength: Option<f64>,
+    island_curve: IslandCurve,
     flatten: Option<bool>,
     use_shading: Option<bool>,
+    color_ramp: ColorRamp,
     oninterpolationchange: EventHandler<Interpolation>,
     onseedchange: EventHandler<i64>,
     onnoisechange: EventHandler<NoiseKind>,
+    ondistancechange: EventHandler<Distance>,
+    oncellreturnchange: EventHandler<CellReturn>,
+    onwarpmodechange: EventHandler<WarpMode>,
+    onwarpstrengthchange: EventHandler<f64>,
+    onwarpfrequencychange: EventHandler<f64>,
+    onfrequencychange: EventHandler<f64>,
+    onoffsetxchange: EventHandler<f64>,
+    onoffsetychange: EventHandler<f64>,
+    onrotationchange: EventHandler<f64>,
+    ontilingchange: EventHandler<Tiling>,
     onwidthchange: EventHandler<i64>,
     onheightchange: EventHandler<i64>,
     onheightmapchange: EventHandler<HeightmapKind>,
+    onimagechange: EventHandler<(HeightmapImage, Vec<u8>)>,
     onoctavechange: EventHandler<i64>,
     onpersistencechange: EventHandler<f64>,
     onlacunaritychange: EventHandler<f64>,
+    onfractalmodechange: EventHandler<FractalMode>,
+    onrangemodechange: EventHandler<RangeMode>,
+    onerosioniterationschange: EventHandler<i64>,
+    onerosioninertiachange: EventHandler<f64>,
+    onsedimentcapacitychange: EventHandler<f64>,
+    ondepositionratechange: EventHandler<f64>,
+    onerosionratechange: EventHandler<f64>,
+    onevaporationchange: EventHandler<f64>,
+    onthermaliterationschange: EventHandler<i64>,
+    ontalusanglechange: EventHandler<f64>,
+    onneighbourhoodchange: EventHandler<Neighbourhood>,
+    onislandmaskchange: EventHandler<IslandMask>,
+    onislandcenterxchange: EventHandler<f64>,
+    onislandcenterychange: EventHandler<f64>,
+    onislandradiuschange: EventHandler<f64>,
+    onislandstrengthchange: EventHandler<f64>,
+    onislandcurvechange: EventHandler<IslandCurve>,
     onflattenchange: EventHandler<bool>,
     onuseshadingchange: EventHandler<bool>,
+    oncolorrampchange: EventHandler<ColorRamp>,
+    onimportsettings: EventHandler<GeneratorSettings>,
+    onexportsettings: EventHandler<SettingsFormat>,
+    onresetsettings: EventHandler<()>,
+    ondownload: EventHandler<()>,
+    ondownloadheightmap: EventHandler<(HeightmapFormat, f64, f64)>,
+    ondownloadmesh: EventHandler<(MeshFormat, MeshColors, f64)>,
+}
+
+/// Built-in color ramps, then the ones saved by the user.
+fn ramp_presets(saved: &BTreeMap<String, ColorRamp>) -> Vec<(String, ColorRamp)> {
+    ColorRampPreset::iter()
+        .map(|preset| (preset.to_string(), preset.ramp()))
+        .chain(
+            saved
+                .iter()
+                .map(|(name, ramp)| (name.clone(), ramp.clone())),
+        )
+        .collect()
 }
 
 #[component]
 pub fn element(props: Props) -> Element {
-    let mut noise_type = use_signal(|| props.noise.unwrap_or_default());
-    let mut heightmap_type = use_signal(|| props.heightmap.unwrap_or_default());
+    // Follow settings changed from outside the panel, like imported ones.
+    let mut noise_type = use_synced_signal(props.noise, NoiseKind::default);
+    let mut heightmap_type = use_synced_signal(props.heightmap, HeightmapKind::default);
+    let mut warp_mode = use_synced_signal(props.warp_mode, WarpMode::default);
+    let mut tiling = use_synced_signal(props.tiling, Tiling::default);
+    let mut erosion_iterations = use_synced_signal(props.erosion_iterations, i64::default);
+    let mut thermal_iterations = use_synced_signal(props.thermal_iterations, i64::default);
+    let mut island_mask_type = use_synced_signal(props.island_mask, IslandMask::default);
+    let mut heightmap_format = use_signal(HeightmapFormat::default);
+    let mut export_min = use_signal(|| 0.0);
+    let mut export_max = use_signal(|| 1.0);
+    let mut mesh_format = use_signal(MeshFormat::default);
+    let mut mesh_colors = use_signal(MeshColors::default);
+    let mut vertical_scale = use_signal(|| 64.0);
+    let mut settings_format = use_signal(SettingsFormat::default);
+    let mut saved_ramps = use_signal(storage::read_presets);
+    let mut ramp_name = use_signal(String::new);
+
+    let choices = ramp_presets(&saved_ramps.read());
+    let current_ramp = choices
+        .iter()
+        .position(|(_, ramp)| *ramp == props.color_ramp);
+    let color_ramp = props.color_ramp.clone();
+    let is_built_in = ColorRampPreset::iter().any(|preset| preset.to_string() == *ramp_name.read());
+    let is_saved = saved_ramps.read().contains_key(&*ramp_name.read());
 
     rsx! {
         div {
@@ -50,7 +170,7 @@ pub fn element(props: Props) -> Element {
                     td {":"}
                     td { EnumSelect {
                         value: props.interpolation,
-                        disabled: *noise_type.read() == NoiseKind::Simplex ||
+                        disabled: *noise_type.read() == NoiseKind::Simplex || *noise_type.read() == NoiseKind::Worley ||
                             (props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal),
                         onchange: move|interpolation| props.oninterpolationchange.call(interpolation)}
                     }
@@ -67,15 +187,103 @@ pub fn element(props: Props) -> Element {
                         }}
                     }
                 }
+                tr {
+                    td {"Distance"}
+                    td {":"}
+                    td { EnumSelect {
+                        value: props.distance,
+                        disabled: *noise_type.read() != NoiseKind::Worley ||
+                            (props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal),
+                        onchange: move|distance| props.ondistancechange.call(distance)}
+                    }
+                }
+                tr {
+                    td {"Cell return"}
+                    td {":"}
+                    td { EnumSelect {
+                        value: props.cell_return,
+                        disabled: *noise_type.read() != NoiseKind::Worley ||
+                            (props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal),
+                        onchange: move|cell_return| props.oncellreturnchange.call(cell_return)}
+                    }
+                }
+                tr {
+                    td {"Frequency"}
+                    td {":"}
+                    td { Slidebar { min: 0.25, max: 16.0, step: 0.25, value: props.frequency,
+                        disabled: props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal,
+                        onchange: move|frequency| props.onfrequencychange.call(frequency)}}
+                }
+                tr {
+                    td {"Offset X"}
+                    td {":"}
+                    td { Slidebar { min: -2.0, max: 2.0, step: 0.01, value: props.offset_x,
+                        disabled: props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal,
+                        onchange: move|offset| props.onoffsetxchange.call(offset)}}
+                }
+                tr {
+                    td {"Offset Y"}
+                    td {":"}
+                    td { Slidebar { min: -2.0, max: 2.0, step: 0.01, value: props.offset_y,
+                        disabled: props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal,
+                        onchange: move|offset| props.onoffsetychange.call(offset)}}
+                }
+                tr {
+                    td {"Rotation"}
+                    td {":"}
+                    td { Slidebar { min: 0.0, max: 360.0, step: 1.0, value: props.rotation,
+                        disabled: *tiling.read() != Tiling::None || (props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal),
+                        onchange: move|rotation| props.onrotationchange.call(rotation)}}
+                }
+                tr {
+                    td {"Domain warp"}
+                    td {":"}
+                    td { EnumSelect {
+                        value: props.warp_mode,
+                        disabled: props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal,
+                        onchange: move|mode| {
+                            *warp_mode.write() = mode;
+                            props.onwarpmodechange.call(mode);
+                        }}
+                    }
+                }
+                tr {
+                    td {"Warp strength"}
+                    td {":"}
+                    td { Slidebar { min: 0.0, max: 2.0, step: 0.05, value: props.warp_strength,
+                        disabled: *warp_mode.read() == WarpMode::None ||
+                            (props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal),
+                        onchange: move|strength| props.onwarpstrengthchange.call(strength)}}
+                }
+                tr {
+                    td {"Warp frequency"}
+                    td {":"}
+                    td { Slidebar { min: 0.25, max: 8.0, step: 0.25, value: props.warp_frequency,
+                        disabled: *warp_mode.read() == WarpMode::None ||
+                            (props.generator_type != GeneratorType::Noise && *heightmap_type.read() != HeightmapKind::Fractal),
+                        onchange: move|frequency| props.onwarpfrequencychange.call(frequency)}}
+                }
+                tr {
+                    td {"Tiling"}
+                    td {":"}
+                    td { EnumSelect {
+                        value: props.tiling,
+                        disabled: props.generator_type != GeneratorType::Noise && *heightmap_type.read() == HeightmapKind::Image,
+                        onchange: move|value| {
+                            *tiling.write() = value;
+                            props.ontilingchange.call(value);
+                        }}
+                    }
+                }
                 tr {
                     td {"Width"}
                     td {":"}
-                    td { Slidebar { min: 64, max: 1024, value: props.width, onchange: move|width| props.onwidthchange.call(width)}}
+                    td { Slidebar { min: MIN_SIZE as i64, max: MAX_SIZE as i64, value: props.width, onchange: move|width| props.onwidthchange.call(width)}}
                 }
                 tr {
                     td {"Height"}
                     td {":"}
-                    td { Slidebar { min: 64, max: 1024, value: props.height, onchange: move|height| props.onheightchange.call(height)}}
+                    td { Slidebar { min: MIN_SIZE as i64, max: MAX_SIZE as i64, value: props.height, onchange: move|height| props.onheightchange.call(height)}}
                 }
                 {
                     if props.generator_type != GeneratorType::Noise {
@@ -91,6 +299,28 @@ pub fn element(props: Props) -> Element {
                                     }}
                                 }
                             }
+                            tr {
+                                td {"Image"}
+                                td {":"}
+                                td { input {
+                                    r#type: "file",
+                                    class: "full-width",
+                                    accept: ".png,.pgm",
+                                    disabled: *heightmap_type.read() != HeightmapKind::Image,
+                                    onchange: move|e| async move {
+                                        if let Some(files) = e.files() {
+                                            if let Some(name) = files.files().first() {
+                                                if let Some(bytes) = files.read_file(name).await {
+                                                    match HeightmapImage::decode(&bytes) {
+                                                        Ok(image) => props.onimagechange.call((image, bytes)),
+                                                        Err(e) => error!("Failed to import {name}: {e}"),
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }}
+                            }
                             tr {
                                 td {"Octave"}
                                 td {":"}
@@ -106,6 +336,125 @@ pub fn element(props: Props) -> Element {
                                 td {":"}
                                 td { Slidebar { min: 1.0, max: 4.0, step: 0.1, value: props.lacunarity, disabled: *heightmap_type.read() != HeightmapKind::Fractal, onchange: move|lacunarity| props.onlacunaritychange.call(lacunarity)}}
                             }
+                            tr {
+                                td {"Fractal mode"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: props.fractal_mode,
+                                    disabled: *heightmap_type.read() != HeightmapKind::Fractal,
+                                    onchange: move|mode| props.onfractalmodechange.call(mode)}
+                                }
+                            }
+                            tr {
+                                td {"Range"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: props.range_mode,
+                                    disabled: *heightmap_type.read() != HeightmapKind::Fractal,
+                                    onchange: move|range_mode| props.onrangemodechange.call(range_mode)}
+                                }
+                            }
+                            tr {
+                                td {"Erosion droplets"}
+                                td {":"}
+                                td { Slidebar { min: 0, max: 200000, step: 5000, value: props.erosion_iterations, onchange: move|iterations| {
+                                    *erosion_iterations.write() = iterations;
+                                    props.onerosioniterationschange.call(iterations);
+                                }}}
+                            }
+                            tr {
+                                td {"Inertia"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: props.erosion_inertia, disabled: *erosion_iterations.read() == 0, onchange: move|inertia| props.onerosioninertiachange.call(inertia)}}
+                            }
+                            tr {
+                                td {"Sediment capacity"}
+                                td {":"}
+                                td { Slidebar { min: 1.0, max: 16.0, step: 0.5, value: props.sediment_capacity, disabled: *erosion_iterations.read() == 0, onchange: move|capacity| props.onsedimentcapacitychange.call(capacity)}}
+                            }
+                            tr {
+                                td {"Deposition rate"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: props.deposition_rate, disabled: *erosion_iterations.read() == 0, onchange: move|rate| props.ondepositionratechange.call(rate)}}
+                            }
+                            tr {
+                                td {"Erosion rate"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: props.erosion_rate, disabled: *erosion_iterations.read() == 0, onchange: move|rate| props.onerosionratechange.call(rate)}}
+                            }
+                            tr {
+                                td {"Evaporation"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 0.1, step: 0.005, value: props.evaporation, disabled: *erosion_iterations.read() == 0, onchange: move|evaporation| props.onevaporationchange.call(evaporation)}}
+                            }
+                            tr {
+                                td {"Thermal iterations"}
+                                td {":"}
+                                td { Slidebar { min: 0, max: 100, value: props.thermal_iterations, onchange: move|iterations| {
+                                    *thermal_iterations.write() = iterations;
+                                    props.onthermaliterationschange.call(iterations);
+                                }}}
+                            }
+                            tr {
+                                td {"Talus angle"}
+                                td {":"}
+                                td { Slidebar { min: 5.0, max: 85.0, step: 1.0, value: props.talus_angle, disabled: *thermal_iterations.read() == 0, onchange: move|angle| props.ontalusanglechange.call(angle)}}
+                            }
+                            tr {
+                                td {"Neighbourhood"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: props.neighbourhood,
+                                    disabled: *thermal_iterations.read() == 0,
+                                    onchange: move|neighbourhood| props.onneighbourhoodchange.call(neighbourhood)}
+                                }
+                            }
+                            tr {
+                                td {"Island mask"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: props.island_mask,
+                                    onchange: move|island_mask| {
+                                        *island_mask_type.write() = island_mask;
+                                        props.onislandmaskchange.call(island_mask);
+                                    }}
+                                }
+                            }
+                            tr {
+                                td {"Island center X"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: props.island_center_x, disabled: *island_mask_type.read() == IslandMask::None, onchange: move|x| props.onislandcenterxchange.call(x)}}
+                            }
+                            tr {
+                                td {"Island center Y"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: props.island_center_y, disabled: *island_mask_type.read() == IslandMask::None, onchange: move|y| props.onislandcenterychange.call(y)}}
+                            }
+                            tr {
+                                td {"Island radius"}
+                                td {":"}
+                                td { Slidebar { min: 0.1, max: 1.0, step: 0.05, value: props.island_radius, disabled: *island_mask_type.read() == IslandMask::None, onchange: move|radius| props.onislandradiuschange.call(radius)}}
+                            }
+                            tr {
+                                td {"Island strength"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: props.island_strength, disabled: *island_mask_type.read() == IslandMask::None, onchange: move|strength| props.onislandstrengthchange.call(strength)}}
+                            }
+                            tr {
+                                td {"Island curve"}
+                                td {":"}
+                                td { input {
+                                    r#type: "text",
+                                    class: "full-width",
+                                    title: "Height by distance to the center, as distance:height points",
+                                    value: props.island_curve.to_string(),
+                                    disabled: *island_mask_type.read() != IslandMask::Curve,
+                                    onchange: move|e| match e.value().parse::<IslandCurve>() {
+                                        Ok(curve) => props.onislandcurvechange.call(curve),
+                                        Err(e) => error!("Failed to read island curve: {e}"),
+                                    }
+                                }}
+                            }
                             tr {
                                 td {"Flatten"}
                                 td {":"}
@@ -127,6 +476,68 @@ pub fn element(props: Props) -> Element {
                                                 onchange: move|e| props.onuseshadingchange.call(e.value().parse::<bool>().unwrap_or_default())
                                             }}
                                         }
+                                        tr {
+                                            td {"Color ramp preset"}
+                                            td {":"}
+                                            td { select {
+                                                class: "full-width",
+                                                onchange: move|e| {
+                                                    let choice = e.value().parse::<usize>().ok().and_then(|i| ramp_presets(&saved_ramps.read()).into_iter().nth(i));
+                                                    if let Some((name, ramp)) = choice {
+                                                        ramp_name.set(name);
+                                                        props.oncolorrampchange.call(ramp);
+                                                    }
+                                                },
+                                                option { value: "", disabled: true, selected: current_ramp.is_none(), "Custom" }
+                                                {
+                                                    choices.iter().enumerate().map(|(i, (name, _))| rsx! {
+                                                        option { value: i as i64, selected: current_ramp == Some(i), "{name}" }
+                                                    })
+                                                }
+                                            }}
+                                        }
+                                        tr {
+                                            td {"Color ramp"}
+                                            td {":"}
+                                            td { ColorRampEditor {
+                                                value: props.color_ramp.clone(),
+                                                onchange: move|ramp| props.oncolorrampchange.call(ramp)
+                                            }}
+                                        }
+                                        tr {
+                                            td {"Preset name"}
+                                            td {":"}
+                                            td { input {
+                                                r#type: "text",
+                                                class: "full-width",
+                                                value: "{ramp_name}",
+                                                oninput: move|e| ramp_name.set(e.value())
+                                            }}
+                                        }
+                                        tr {
+                                            td {}
+                                            td {}
+                                            td {
+                                                button {
+                                                    // Built-in presets keep their ramps.
+                                                    disabled: ramp_name.read().is_empty() || is_built_in,
+                                                    onclick: move|_| {
+                                                        let name = ramp_name.read().clone();
+                                                        saved_ramps.write().insert(name, color_ramp.clone());
+                                                        storage::write_presets(&saved_ramps.read());
+                                                    },
+                                                    "Save preset"
+                                                }
+                                                button {
+                                                    disabled: !is_saved,
+                                                    onclick: move|_| {
+                                                        saved_ramps.write().remove(&*ramp_name.read());
+                                                        storage::write_presets(&saved_ramps.read());
+                                                    },
+                                                    "Delete preset"
+                                                }
+                                            }
+                                        }
                                     }
                                 } else {
                                     rsx! {}
@@ -137,6 +548,132 @@ pub fn element(props: Props) -> Element {
                         rsx! {}
                     }
                 }
+                tr {
+                    td {"Settings format"}
+                    td {":"}
+                    td { EnumSelect {
+                        value: Some(*settings_format.read()),
+                        onchange: move|format| settings_format.set(format)}
+                    }
+                }
+                tr {
+                    td {}
+                    td {}
+                    td { button {
+                        class: "full-width",
+                        onclick: move|_| props.onexportsettings.call(*settings_format.read()),
+                        "Export settings"
+                    }}
+                }
+                tr {
+                    td {"Import settings"}
+                    td {":"}
+                    td { input {
+                        r#type: "file",
+                        class: "full-width",
+                        accept: ".json,.toml",
+                        onchange: move|e| async move {
+                            if let Some(files) = e.files() {
+                                if let Some(name) = files.files().first() {
+                                    let format = name
+                                        .rsplit_once('.')
+                                        .and_then(|(_, extension)| SettingsFormat::from_extension(extension));
+                                    match (format, files.read_file_to_string(name).await) {
+                                        (Some(format), Some(text)) => match GeneratorSettings::decode(&text, format) {
+                                            Ok(settings) => props.onimportsettings.call(fit_size(settings)),
+                                            Err(e) => error!("Failed to import {name}: {e}"),
+                                        },
+                                        _ => error!("Failed to import {name}: expected a JSON or TOML file"),
+                                    }
+                                }
+                            }
+                        }
+                    }}
+                }
+                tr {
+                    td {}
+                    td {}
+                    td { button {
+                        class: "full-width",
+                        onclick: move|_| props.onresetsettings.call(()),
+                        "Reset to defaults"
+                    }}
+                }
+                tr {
+                    td {"Export"}
+                    td {":"}
+                    td { button {
+                        class: "full-width",
+                        onclick: move|_| props.ondownload.call(()),
+                        "Download PNG"
+                    }}
+                }
+                {
+                    if props.generator_type != GeneratorType::Noise {
+                        rsx! {
+                            tr {
+                                td {"Heightmap format"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: Some(*heightmap_format.read()),
+                                    onchange: move|format| heightmap_format.set(format)}
+                                }
+                            }
+                            tr {
+                                td {"Export minimum"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: Some(*export_min.read()), onchange: move|min| export_min.set(min)}}
+                            }
+                            tr {
+                                td {"Export maximum"}
+                                td {":"}
+                                td { Slidebar { min: 0.0, max: 1.0, step: 0.05, value: Some(*export_max.read()), onchange: move|max| export_max.set(max)}}
+                            }
+                            tr {
+                                td {}
+                                td {}
+                                td { button {
+                                    class: "full-width",
+                                    disabled: *export_min.read() >= *export_max.read(),
+                                    onclick: move|_| props.ondownloadheightmap.call((*heightmap_format.read(), *export_min.read(), *export_max.read())),
+                                    "Download heightmap"
+                                }}
+                            }
+                            tr {
+                                td {"Mesh format"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: Some(*mesh_format.read()),
+                                    onchange: move|format| mesh_format.set(format)}
+                                }
+                            }
+                            tr {
+                                td {"Mesh colors"}
+                                td {":"}
+                                td { EnumSelect {
+                                    value: Some(*mesh_colors.read()),
+                                    onchange: move|colors| mesh_colors.set(colors)}
+                                }
+                            }
+                            tr {
+                                td {"Vertical scale"}
+                                td {":"}
+                                td { Slidebar { min: 1.0, max: 256.0, step: 1.0, value: Some(*vertical_scale.read()), onchange: move|scale| vertical_scale.set(scale)}}
+                            }
+                            tr {
+                                td {}
+                                td {}
+                                td { button {
+                                    class: "full-width",
+                                    onclick: move|_| props.ondownloadmesh.call((*mesh_format.read(), *mesh_colors.read(), *vertical_scale.read())),
+                                    "Download mesh"
+                                }}
+                            }
+                        }
+                    } else {
+                        rsx! {}
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,64 @@
+//! Settings of the last session and color ramps saved by the user, kept in the
+//! local storage of the browser.
+
+use crate::permalink;
+use dioxus_logger::tracing::error;
+use map_generator::{ColorRamp, GeneratorSettings, GeneratorType};
+use std::collections::BTreeMap;
+
+const SETTINGS_KEY: &str = "settings";
+const PRESETS_KEY: &str = "color-ramp-presets";
+
+/// Local storage, missing when the browser disables it.
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()
+        .expect("Failed to get window")
+        .local_storage()
+        .ok()
+        .flatten()
+}
+
+/// Map of the last session, if one was saved.
+pub fn read() -> Option<(GeneratorType, GeneratorSettings)> {
+    let json = storage()?.get_item(SETTINGS_KEY).ok().flatten()?;
+
+    match permalink::from_json(&json) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            error!("Failed to read saved settings: {e}");
+            None
+        }
+    }
+}
+
+pub fn write(generator_type: GeneratorType, settings: &GeneratorSettings) {
+    if let Some(storage) = storage() {
+        if let Err(e) =
+            storage.set_item(SETTINGS_KEY, &permalink::to_json(generator_type, settings))
+        {
+            error!("Failed to save settings: {e:?}");
+        }
+    }
+}
+
+/// Color ramps saved by the user, by name.
+pub fn read_presets() -> BTreeMap<String, ColorRamp> {
+    let Some(json) = storage().and_then(|storage| storage.get_item(PRESETS_KEY).ok().flatten())
+    else {
+        return BTreeMap::new();
+    };
+
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        error!("Failed to read saved color ramps: {e}");
+        BTreeMap::new()
+    })
+}
+
+pub fn write_presets(presets: &BTreeMap<String, ColorRamp>) {
+    if let Some(storage) = storage() {
+        let json = serde_json::to_string(presets).expect("Failed to serialize color ramps");
+        if let Err(e) = storage.set_item(PRESETS_KEY, &json) {
+            error!("Failed to save color ramps: {e:?}");
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! Generation in a Web Worker running this same module, so that the page stays
+//! responsive while large maps are generated. `assets/worker.js` loads the
+//! module in the worker and hands it the messages of the page.
+
+use crate::download;
+use dioxus_logger::tracing::error;
+use map_generator::{
+    obj_material, Generator, GeneratorSettings, GeneratorType, HeightmapFormat, HeightmapImage,
+    MeshColors, MeshFormat, Rows,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    error::Error,
+    fmt::Display,
+    rc::Rc,
+};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::js_sys;
+
+/// Requests of the page, sent as JSON. The image of the Image heightmap is
+/// sent apart, as the bytes of its file.
+#[derive(Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+enum Request {
+    Generate {
+        id: u64,
+        generator_type: GeneratorType,
+        settings: GeneratorSettings,
+    },
+    Export {
+        settings: GeneratorSettings,
+        export: Export,
+    },
+}
+
+/// Files downloaded from the worker, which encodes them off the page.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Export {
+    Png(GeneratorType),
+    Heightmap {
+        format: HeightmapFormat,
+        min: f64,
+        max: f64,
+    },
+    Mesh {
+        format: MeshFormat,
+        colors: MeshColors,
+        vertical_scale: f64,
+    },
+}
+
+/// A running worker, terminated when dropped.
+struct Instance {
+    worker: web_sys::Worker,
+    _onmessage: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    _onerror: Closure<dyn FnMut(web_sys::ErrorEvent)>,
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
+
+struct State {
+    instance: Option<Instance>,
+    /// Number of workers started, the events of replaced ones are ignored.
+    count: u64,
+    /// Whether the current worker has answered, workers failing before that
+    /// are not restarted as they would fail again.
+    started: bool,
+    /// Identifier of the latest request, results of older ones are stale.
+    latest: u64,
+    /// PNG or PGM file of the Image heightmap, sent again to new workers.
+    image: Option<Vec<u8>>,
+    onrows: Rc<dyn Fn(web_sys::ImageData, f64)>,
+}
+
+/// Page side of the worker. Requests are sent as they come: the worker drops
+/// the generation it is running for a newer one, and encodes exports in
+/// order. A worker that panics is replaced, without its pending requests.
+pub struct GenerationWorker {
+    state: Rc<RefCell<State>>,
+}
+
+impl GenerationWorker {
+    /// Starts the worker, `onrows` receives the parts of the map of the latest
+    /// request with their first row: a preview of the whole map, then the
+    /// final rows.
+    pub fn new(onrows: impl Fn(web_sys::ImageData, f64) + 'static) -> Self {
+        let state = Rc::new(RefCell::new(State {
+            instance: None,
+            count: 0,
+            started: false,
+            latest: 0,
+            image: None,
+            onrows: Rc::new(onrows),
+        }));
+        spawn(&state);
+
+        Self { state }
+    }
+
+    pub fn set_image(&self, bytes: Vec<u8>) {
+        let mut state = self.state.borrow_mut();
+        if let Some(instance) = &state.instance {
+            post_image(&instance.worker, &bytes);
+        }
+        state.image = Some(bytes);
+    }
+
+    pub fn generate(&self, generator_type: GeneratorType, settings: GeneratorSettings) {
+        let mut state = self.state.borrow_mut();
+        state.latest += 1;
+        let id = state.latest;
+
+        post(
+            &state,
+            &Request::Generate {
+                id,
+                generator_type,
+                settings,
+            },
+        );
+    }
+
+    /// Downloads the files of `export` once the worker has encoded them.
+    pub fn export(&self, settings: GeneratorSettings, export: Export) {
+        post(&self.state.borrow(), &Request::Export { settings, export });
+    }
+}
+
+fn spawn(state: &Rc<RefCell<State>>) {
+    let options = web_sys::WorkerOptions::new();
+    options.set_type(web_sys::WorkerType::Module);
+    let worker =
+        web_sys::Worker::new_with_options("worker.js", &options).expect("Failed to start worker");
+    let count = state.borrow().count + 1;
+
+    let onmessage = Closure::<dyn FnMut(_)>::new({
+        let state = Rc::downgrade(state);
+        move |e: web_sys::MessageEvent| {
+            if let Some(state) = state.upgrade() {
+                on_result(&state, count, &e.data());
+            }
+        }
+    });
+    let onerror = Closure::<dyn FnMut(_)>::new({
+        let state = Rc::downgrade(state);
+        move |e: web_sys::ErrorEvent| {
+            error!("Generation worker failed: {}", e.message());
+            if let Some(state) = state.upgrade() {
+                if state.borrow().started {
+                    restart(&state, count);
+                }
+            }
+        }
+    });
+    worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+
+    let mut state = state.borrow_mut();
+    if let Some(image) = &state.image {
+        post_image(&worker, image);
+    }
+    state.count = count;
+    state.started = false;
+    state.instance = Some(Instance {
+        worker,
+        _onmessage: onmessage,
+        _onerror: onerror,
+    });
+}
+
+/// Replaces the worker `count` unless it has already been.
+fn restart(state: &Rc<RefCell<State>>, count: u64) {
+    let state = Rc::downgrade(state);
+    // Deferred, as the handlers of the failed worker are running.
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Some(state) = state.upgrade().filter(|s| s.borrow().count == count) {
+            spawn(&state);
+        }
+    });
+}
+
+fn on_result(state: &Rc<RefCell<State>>, count: u64, result: &JsValue) {
+    if state.borrow().count != count {
+        return;
+    }
+    state.borrow_mut().started = true;
+
+    let get = |key: &str| js_sys::Reflect::get(result, &key.into()).unwrap_or_default();
+    if let Some(message) = get("panic").as_string() {
+        error!("Generation worker panicked: {message}");
+        restart(state, count);
+    } else if let Some(message) = get("error").as_string() {
+        error!("Generation failed: {message}");
+    } else if let Some(filename) = get("download").as_string() {
+        let data = get("data").unchecked_into::<js_sys::Uint8Array>().to_vec();
+        download::download(
+            &filename,
+            &get("mime").as_string().unwrap_or_default(),
+            &data,
+        );
+    } else if get("id").as_f64() == Some(state.borrow().latest as f64) {
+        let onrows = state.borrow().onrows.clone();
+        onrows(
+            get("image").unchecked_into(),
+            get("start").as_f64().unwrap_or_default(),
+        );
+    }
+}
+
+fn post(state: &State, request: &Request) {
+    let request = serde_json::to_string(request).expect("Failed to serialize request");
+    if let Some(instance) = &state.instance {
+        instance
+            .worker
+            .post_message(&JsValue::from_str(&request))
+            .expect("Failed to post request");
+    }
+}
+
+fn post_image(worker: &web_sys::Worker, bytes: &[u8]) {
+    let bytes = js_sys::Uint8Array::from(bytes);
+    worker
+        .post_message_with_transfer(&bytes, &js_sys::Array::of1(&bytes.buffer()))
+        .expect("Failed to post image");
+}
+
+thread_local! {
+    /// Worker side generator, kept between requests for its caches.
+    static GENERATOR: RefCell<Option<Generator>> = const { RefCell::new(None) };
+    /// Image received since the last request, applied before the next one.
+    static IMAGE: RefCell<Option<HeightmapImage>> = const { RefCell::new(None) };
+    /// Latest generation request, replacing older ones.
+    static PENDING: RefCell<Option<(u64, GeneratorType, GeneratorSettings)>> =
+        const { RefCell::new(None) };
+    static EXPORTS: RefCell<VecDeque<(GeneratorSettings, Export)>> =
+        const { RefCell::new(VecDeque::new()) };
+    /// Whether requests are being handled, by a task started by an earlier one.
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Reports panics to the page before the module traps, so that it replaces
+/// the worker.
+pub fn set_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        respond([("panic", info.to_string().into())], &js_sys::Array::new());
+    }));
+}
+
+/// Worker side handler of the requests of the page.
+#[wasm_bindgen]
+pub fn on_worker_message(message: JsValue) {
+    if let Some(bytes) = message.dyn_ref::<js_sys::Uint8Array>() {
+        match HeightmapImage::decode(&bytes.to_vec()) {
+            Ok(image) => IMAGE.set(Some(image)),
+            Err(e) => respond_error(e),
+        }
+        return;
+    }
+
+    let request = match message.as_string().map(|r| serde_json::from_str(&r)) {
+        Some(Ok(request)) => request,
+        Some(Err(e)) => return respond_error(e),
+        None => return respond_error("expected a JSON request"),
+    };
+    match request {
+        Request::Generate {
+            id,
+            generator_type,
+            settings,
+        } => PENDING.set(Some((id, generator_type, settings))),
+        Request::Export { settings, export } => {
+            EXPORTS.with_borrow_mut(|exports| exports.push_back((settings, export)))
+        }
+    }
+
+    if !RUNNING.get() {
+        wasm_bindgen_futures::spawn_local(run());
+    }
+}
+
+/// Handles the requests until none is left. Exports come first, and newer
+/// generation requests cancel the running one between two parts of the map.
+async fn run() {
+    RUNNING.set(true);
+    let mut generator = GENERATOR.take();
+
+    loop {
+        if let Some((settings, export)) = EXPORTS.with_borrow_mut(VecDeque::pop_front) {
+            match export_files(prepare(&mut generator, settings), export) {
+                Ok(files) => {
+                    for (filename, mime, data) in files {
+                        let data = js_sys::Uint8Array::from(data.as_slice());
+                        respond(
+                            [
+                                ("download", filename.into()),
+                                ("mime", mime.into()),
+                                ("data", data.clone().into()),
+                            ],
+                            &js_sys::Array::of1(&data.buffer()),
+                        );
+                    }
+                }
+                Err(e) => respond_error(e),
+            }
+        } else if let Some((id, generator_type, settings)) = PENDING.take() {
+            let width = settings.width as u32;
+            let parts = prepare(&mut generator, settings)
+                .generate_progressive()
+                .generator_type(generator_type)
+                .call();
+
+            for Rows { start, data } in parts {
+                match web_sys::ImageData::new_with_u8_clamped_array(
+                    wasm_bindgen::Clamped(&data),
+                    width,
+                ) {
+                    Ok(image) => respond(
+                        [
+                            ("id", JsValue::from(id as f64)),
+                            ("start", JsValue::from(start as f64)),
+                            ("image", image.into()),
+                        ],
+                        &js_sys::Array::new(),
+                    ),
+                    Err(_) => {
+                        respond_error("failed to create image data");
+                        break;
+                    }
+                }
+
+                yield_now().await;
+                if PENDING.with_borrow(Option::is_some) {
+                    break;
+                }
+            }
+        } else {
+            break;
+        }
+    }
+
+    GENERATOR.set(generator);
+    RUNNING.set(false);
+}
+
+fn prepare(generator: &mut Option<Generator>, settings: GeneratorSettings) -> &mut Generator {
+    let generator = match generator {
+        Some(generator) => {
+            generator.set_settings(settings);
+            generator
+        }
+        None => generator.insert(Generator::from(settings)),
+    };
+    if let Some(image) = IMAGE.take() {
+        generator.set_image(Some(image));
+    }
+    generator
+}
+
+type File = (String, &'static str, Vec<u8>);
+
+fn export_files(generator: &Generator, export: Export) -> Result<Vec<File>, Box<dyn Error>> {
+    Ok(match export {
+        Export::Png(generator_type) => vec![(
+            format!("{generator_type}.png"),
+            "image/png",
+            generator.export_png(generator_type)?,
+        )],
+        Export::Heightmap { format, min, max } => vec![(
+            format!("Heightmap.{}", format.extension()),
+            format.mime(),
+            generator
+                .export_heightmap()
+                .format(format)
+                .min(min)
+                .max(max)
+                .call()?,
+        )],
+        Export::Mesh {
+            format,
+            colors,
+            vertical_scale,
+        } => {
+            let mesh = generator
+                .export_mesh()
+                .format(format)
+                .colors(colors)
+                .vertical_scale(vertical_scale)
+                .name("Mesh")
+                .call()?;
+            let mut files = vec![(format!("Mesh.{}", format.extension()), format.mime(), mesh)];
+
+            if colors == MeshColors::Texture && format == MeshFormat::Obj {
+                let png = generator.export_png(GeneratorType::ColoredMap)?;
+                files.push(("Mesh.mtl".into(), "model/mtl", obj_material("Mesh")));
+                files.push(("Mesh.png".into(), "image/png", png));
+            }
+            files
+        }
+    })
+}
+
+/// Lets the worker receive the messages of the page.
+async fn yield_now() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        scope()
+            .set_timeout_with_callback(&resolve)
+            .expect("Failed to set timeout");
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .expect("Failed to yield");
+}
+
+fn scope() -> web_sys::DedicatedWorkerGlobalScope {
+    js_sys::global().unchecked_into()
+}
+
+fn respond<const N: usize>(entries: [(&str, JsValue); N], transfer: &js_sys::Array) {
+    let result = js_sys::Object::new();
+    for (key, value) in entries {
+        js_sys::Reflect::set(&result, &key.into(), &value).expect("Failed to set result");
+    }
+    scope()
+        .post_message_with_transfer(&result, transfer)
+        .expect("Failed to post result");
+}
+
+fn respond_error(error: impl Display) {
+    respond([("error", error.to_string().into())], &js_sys::Array::new());
+}